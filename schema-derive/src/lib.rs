@@ -0,0 +1,293 @@
+//! Implements `#[derive(Record)]` for the schema crate
+//!
+//! See `schema::typed` for the supported attributes.
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{
+    parse_macro_input, parse_quote, Data, DeriveInput, Expr, Field, Fields, GenericArgument, Ident,
+    LitStr, Path, PathArguments, Type,
+};
+
+#[proc_macro_derive(Record, attributes(schema))]
+pub fn derive_record(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    match expand(input) {
+        Ok(tokens) => tokens.into(),
+        Err(err) => err.into_compile_error().into(),
+    }
+}
+
+#[derive(Default)]
+struct FieldAttrs {
+    key: bool,
+    nullable: bool,
+    rename: Option<String>,
+    default: Option<Expr>,
+}
+
+/// A field of the struct and how it is stored
+struct RecordField<'a> {
+    ident: &'a Ident,
+    name: String,
+    /// The stored type, i.e., `T` for fields of type `Option<T>`
+    vtype: &'a Type,
+    nullable: bool,
+    key: bool,
+    default: Option<Expr>,
+}
+
+fn parse_attrs(field: &Field) -> syn::Result<FieldAttrs> {
+    let mut attrs = FieldAttrs::default();
+
+    for attr in field.attrs.iter() {
+        if !attr.path().is_ident("schema") {
+            continue;
+        }
+
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("key") {
+                attrs.key = true;
+            } else if meta.path.is_ident("nullable") {
+                attrs.nullable = true;
+            } else if meta.path.is_ident("rename") {
+                let name: LitStr = meta.value()?.parse()?;
+                attrs.rename = Some(name.value());
+            } else if meta.path.is_ident("default") {
+                attrs.default = Some(meta.value()?.parse()?);
+            } else {
+                return Err(meta.error("unknown schema attribute"));
+            }
+
+            Ok(())
+        })?;
+    }
+
+    Ok(attrs)
+}
+
+/// The path of the schema crate, which can be set with `#[schema(crate = "path")]`
+fn parse_crate_path(input: &DeriveInput) -> syn::Result<Path> {
+    let mut path: Path = parse_quote!(::schema);
+
+    for attr in input.attrs.iter() {
+        if !attr.path().is_ident("schema") {
+            continue;
+        }
+
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("crate") {
+                let name: LitStr = meta.value()?.parse()?;
+                path = name.parse()?;
+                Ok(())
+            } else {
+                Err(meta.error("unknown schema attribute"))
+            }
+        })?;
+    }
+
+    Ok(path)
+}
+
+/// Returns `T` if `ty` is `Option<T>`
+fn option_inner(ty: &Type) -> Option<&Type> {
+    let Type::Path(path) = ty else {
+        return None;
+    };
+
+    let segment = path.path.segments.last()?;
+    if segment.ident != "Option" {
+        return None;
+    }
+
+    let PathArguments::AngleBracketed(args) = &segment.arguments else {
+        return None;
+    };
+
+    match args.args.first() {
+        Some(GenericArgument::Type(inner)) if args.args.len() == 1 => Some(inner),
+        _ => None,
+    }
+}
+
+fn parse_field(field: &Field) -> syn::Result<RecordField<'_>> {
+    let attrs = parse_attrs(field)?;
+    let ident = field.ident.as_ref().unwrap();
+    let inner = option_inner(&field.ty);
+
+    if attrs.nullable && inner.is_none() {
+        return Err(syn::Error::new_spanned(
+            &field.ty,
+            "nullable fields must be of type Option<T>",
+        ));
+    }
+
+    if attrs.key && inner.is_some() {
+        return Err(syn::Error::new_spanned(
+            &field.ty,
+            "the key field cannot be nullable",
+        ));
+    }
+
+    Ok(RecordField {
+        ident,
+        name: attrs.rename.unwrap_or_else(|| ident.to_string()),
+        vtype: inner.unwrap_or(&field.ty),
+        nullable: inner.is_some(),
+        key: attrs.key,
+        default: attrs.default,
+    })
+}
+
+fn expand(input: DeriveInput) -> syn::Result<TokenStream2> {
+    let ident = &input.ident;
+    let krate = parse_crate_path(&input)?;
+
+    if !input.generics.params.is_empty() {
+        return Err(syn::Error::new_spanned(
+            &input.generics,
+            "Record cannot be derived for generic types",
+        ));
+    }
+
+    let Data::Struct(data) = &input.data else {
+        return Err(syn::Error::new_spanned(
+            ident,
+            "Record can only be derived for structs",
+        ));
+    };
+
+    let Fields::Named(named) = &data.fields else {
+        return Err(syn::Error::new_spanned(
+            ident,
+            "Record can only be derived for structs with named fields",
+        ));
+    };
+
+    let fields = named
+        .named
+        .iter()
+        .map(parse_field)
+        .collect::<syn::Result<Vec<_>>>()?;
+
+    let mut keys = fields.iter().filter(|field| field.key);
+    let Some(key) = keys.next() else {
+        return Err(syn::Error::new_spanned(
+            ident,
+            "Record needs a field marked with #[schema(key)]",
+        ));
+    };
+
+    if let Some(other) = keys.next() {
+        return Err(syn::Error::new_spanned(
+            other.ident,
+            "only one field can be marked with #[schema(key)]",
+        ));
+    }
+
+    let key_ident = key.ident;
+    let key_type = key.vtype;
+
+    let add_fields = fields.iter().map(|field| {
+        let name = &field.name;
+        let vtype = field.vtype;
+        let nullable = field.nullable;
+        let default = match &field.default {
+            Some(expr) => quote! { ::std::option::Option::Some(#krate::Value::from(#expr)) },
+            None => quote! { ::std::option::Option::None },
+        };
+
+        quote! {
+            .add_field_with_options(
+                #name,
+                <#vtype as #krate::HasValueType>::value_type(),
+                #krate::FieldOptions {
+                    nullable: #nullable,
+                    default: #default,
+                    ..::std::default::Default::default()
+                },
+            )
+        }
+    });
+
+    let set_fields = fields.iter().map(|field| {
+        let name = &field.name;
+        let fident = field.ident;
+
+        let value = if field.nullable {
+            quote! {
+                match &self.#fident {
+                    ::std::option::Option::Some(value) => {
+                        #krate::Value::from(::std::clone::Clone::clone(value))
+                    }
+                    ::std::option::Option::None => #krate::Value::Null,
+                }
+            }
+        } else {
+            quote! { #krate::Value::from(::std::clone::Clone::clone(&self.#fident)) }
+        };
+
+        quote! { .set_field_from_value(#name, &#value) }
+    });
+
+    let read_fields = fields.iter().map(|field| {
+        let name = &field.name;
+        let fident = field.ident;
+        let vtype = field.vtype;
+
+        let convert = if field.nullable {
+            quote! { #krate::typed::nullable_value_into::<#vtype> }
+        } else {
+            quote! { #krate::typed::value_into::<#vtype> }
+        };
+
+        quote! { #fident: #convert(#name, schema.get_field(entry, #name)?)? }
+    });
+
+    Ok(quote! {
+        impl #krate::Record for #ident {
+            fn try_schema() -> ::std::result::Result<#krate::Schema, #krate::SchemaError> {
+                #krate::SchemaBuilder::new(
+                    <#key_type as #krate::HasValueType>::value_type()
+                )
+                #(#add_fields)*
+                .try_build()
+            }
+
+            fn key(&self) -> #krate::Value {
+                #krate::Value::from(::std::clone::Clone::clone(&self.#key_ident))
+            }
+
+            fn try_to_entry(
+                &self,
+            ) -> ::std::result::Result<#krate::DataEntry, #krate::SchemaError> {
+                static SCHEMA: ::std::sync::OnceLock<
+                    ::std::result::Result<#krate::Schema, #krate::SchemaError>,
+                > = ::std::sync::OnceLock::new();
+
+                let schema = match SCHEMA.get_or_init(<Self as #krate::Record>::try_schema) {
+                    ::std::result::Result::Ok(schema) => schema,
+                    ::std::result::Result::Err(err) => {
+                        return ::std::result::Result::Err(::std::clone::Clone::clone(err))
+                    }
+                };
+
+                schema
+                    .build_entry()
+                    #(#set_fields)*
+                    .try_build()
+            }
+
+            fn from_entry(
+                schema: &#krate::Schema,
+                entry: &#krate::DataEntry,
+            ) -> ::std::result::Result<Self, #krate::SchemaError> {
+                ::std::result::Result::Ok(Self {
+                    #(#read_fields,)*
+                })
+            }
+        }
+    })
+}
@@ -0,0 +1,123 @@
+use schema as renamed;
+use schema::{Record, SchemaError, Value, ValueType};
+
+#[derive(Record, Debug, Clone, PartialEq)]
+struct User {
+    #[schema(key)]
+    id: u64,
+    #[schema(rename = "user_name")]
+    name: String,
+    #[schema(default = 18u64)]
+    age: u8,
+    #[schema(nullable)]
+    email: Option<String>,
+    avatar: Option<Vec<u8>>,
+}
+
+#[test]
+fn derived_schema() {
+    let schema = User::schema();
+
+    assert_eq!(schema.get_key_type(), ValueType::U64);
+    assert_eq!(
+        schema.get_field_types(),
+        &vec![
+            ("id".to_string(), ValueType::U64),
+            ("user_name".to_string(), ValueType::String),
+            ("age".to_string(), ValueType::U8),
+            ("email".to_string(), ValueType::String),
+            ("avatar".to_string(), ValueType::Bytes),
+        ]
+    );
+
+    let options = schema.get_field_options("age").unwrap();
+    assert_eq!(options.default, Some(Value::U64(18)));
+    assert!(!options.nullable);
+    assert!(schema.get_field_options("email").unwrap().nullable);
+    assert!(schema.get_field_options("avatar").unwrap().nullable);
+}
+
+#[test]
+fn round_trip() {
+    let user = User {
+        id: 5,
+        name: "alice".to_string(),
+        age: 31,
+        email: Some("alice@example.com".to_string()),
+        avatar: None,
+    };
+
+    let schema = User::schema();
+    let entry = user.to_entry();
+
+    assert_eq!(user.key(), Value::U64(5));
+    assert_eq!(
+        schema.get_field(&entry, "user_name").unwrap(),
+        "alice".into()
+    );
+    assert_eq!(schema.get_field(&entry, "avatar").unwrap(), Value::Null);
+    assert_eq!(User::from_entry(&schema, &entry).unwrap(), user);
+}
+
+#[test]
+fn mismatched_entry() {
+    let other = schema::SchemaBuilder::new(ValueType::U64)
+        .add_field("id", ValueType::U64)
+        .add_field("user_name", ValueType::I64)
+        .add_field("age", ValueType::U8)
+        .add_nullable_field("email", ValueType::String)
+        .add_nullable_field("avatar", ValueType::Bytes)
+        .build();
+
+    let entry = other
+        .build_entry()
+        .set_field("id", &1u64)
+        .set_field("user_name", &7i64)
+        .set_field("age", &20u8)
+        .build();
+
+    assert_eq!(
+        User::from_entry(&other, &entry),
+        Err(SchemaError::TypeMismatch {
+            field: "user_name".to_string(),
+            expected: ValueType::String,
+            got: ValueType::I64,
+        })
+    );
+}
+
+#[derive(Record)]
+struct Invalid {
+    #[schema(key)]
+    id: u64,
+    #[schema(default = "eighteen")]
+    age: u8,
+}
+
+#[test]
+fn invalid_schema() {
+    let err = SchemaError::TypeMismatch {
+        field: "age".to_string(),
+        expected: ValueType::U8,
+        got: ValueType::String,
+    };
+
+    assert_eq!(Invalid::try_schema().err(), Some(err.clone()));
+    assert_eq!(Invalid { id: 1, age: 3 }.try_to_entry().err(), Some(err));
+}
+
+#[derive(Record)]
+#[schema(crate = "renamed")]
+struct Renamed {
+    #[schema(key)]
+    id: u64,
+}
+
+#[test]
+fn crate_path() {
+    let entry = Renamed { id: 3 }.to_entry();
+    assert_eq!(
+        Renamed::schema().get_field(&entry, "id").unwrap(),
+        Value::U64(3)
+    );
+}
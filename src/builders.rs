@@ -1,32 +1,242 @@
-use crate::{DataEntry, FieldTypeList, Schema, Value, ValueType};
+use crate::datetime::parse_timezone;
+use crate::key::is_key_type;
+use crate::{
+    encode_value, null_slot, read_field, write_field, DataEntry, Decimal, EntryError, FieldOptions,
+    FieldTypeList, Generator, HasValueType, Schema, SchemaError, SchemaParts, Value, ValueType,
+};
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 use serde::Serialize;
 
+/// Ensures the parameters of a type, such as its timezone, are valid
+fn check_type(vtype: &ValueType) -> Result<(), SchemaError> {
+    match vtype {
+        ValueType::Timestamp {
+            timezone: Some(tz), ..
+        } => match parse_timezone(tz) {
+            Ok(_) => Ok(()),
+            Err(_) => Err(SchemaError::InvalidTimezone(tz.clone())),
+        },
+        ValueType::Decimal { precision, scale } => {
+            if *precision == 0 || *precision > Decimal::MAX_PRECISION || scale > precision {
+                Err(SchemaError::InvalidType(vtype.clone()))
+            } else {
+                Ok(())
+            }
+        }
+        ValueType::List(elem) => check_type(elem),
+        ValueType::Map(key, value) => {
+            if !is_key_type(key) {
+                return Err(SchemaError::UnsupportedKeyType((**key).clone()));
+            }
+            check_type(key)?;
+            check_type(value)
+        }
+        ValueType::Enum(labels) => {
+            for (pos, label) in labels.iter().enumerate() {
+                if labels[..pos].contains(label) {
+                    return Err(SchemaError::InvalidType(vtype.clone()));
+                }
+            }
+
+            if labels.is_empty() || labels.len() > u32::MAX as usize {
+                Err(SchemaError::InvalidType(vtype.clone()))
+            } else {
+                Ok(())
+            }
+        }
+        ValueType::Struct(fields) => {
+            for (pos, (name, mtype)) in fields.iter().enumerate() {
+                // Dots separate the members of a path, e.g., `address.city`
+                if name.contains('.') || fields[..pos].iter().any(|(other, _)| other == name) {
+                    return Err(SchemaError::InvalidType(vtype.clone()));
+                }
+                check_type(mtype)?;
+            }
+            Ok(())
+        }
+        _ => Ok(()),
+    }
+}
+
 pub struct SchemaBuilder {
+    version: u32,
     key: ValueType,
+    key_fields: FieldTypeList,
     fields: FieldTypeList,
+    options: Vec<FieldOptions>,
+    composite_key: bool,
 }
 
 impl SchemaBuilder {
     pub fn new(key: ValueType) -> Self {
         Self {
+            version: 0,
             key,
+            key_fields: Vec::new(),
             fields: Vec::new(),
+            options: Vec::new(),
+            composite_key: false,
         }
     }
 
+    /// Creates a schema whose key consists of multiple components
+    /// Keys are ordered by their first component, then by their second, and so on.
+    /// Duplicate or missing components are reported by `try_build`.
+    pub fn with_composite_key<S: ToString>(components: Vec<(S, ValueType)>) -> Self {
+        let key_fields: FieldTypeList = components
+            .into_iter()
+            .map(|(name, vtype)| (name.to_string(), vtype))
+            .collect();
+
+        // The key type is only a placeholder if there are no components
+        let key = match key_fields.first() {
+            Some((_, key)) => key.clone(),
+            None => ValueType::Bool,
+        };
+
+        Self {
+            key_fields,
+            composite_key: true,
+            ..Self::new(key)
+        }
+    }
+
+    /// Sets the version of the schema, which is stored with every entry
     #[must_use]
-    pub fn build(self) -> Schema {
-        Schema {
+    pub fn set_version(mut self, version: u32) -> Self {
+        self.version = version;
+        self
+    }
+
+    /// Creates the schema, after checking that all types are valid
+    /// and that all default values match the type of their field
+    pub fn try_build(self) -> Result<Schema, SchemaError> {
+        if self.composite_key && self.key_fields.is_empty() {
+            return Err(SchemaError::EmptyKey);
+        }
+
+        for (pos, (name, _)) in self.key_fields.iter().enumerate() {
+            if self.key_fields[..pos]
+                .iter()
+                .any(|(other, _)| other == name)
+            {
+                return Err(SchemaError::DuplicateField(name.clone()));
+            }
+        }
+
+        let key_types = std::iter::once(&self.key).chain(self.key_fields.iter().map(|(_, t)| t));
+
+        for vtype in key_types {
+            if !is_key_type(vtype) {
+                return Err(SchemaError::UnsupportedKeyType(vtype.clone()));
+            }
+            check_type(vtype)?;
+        }
+
+        for (_, vtype) in self.fields.iter() {
+            check_type(vtype)?;
+        }
+
+        // Dots separate a field from its members, see `Schema::get_field`
+        if let Some((_, ftype)) = self.fields.iter().find(|(fname, _)| fname.contains('.')) {
+            return Err(SchemaError::InvalidType(ftype.clone()));
+        }
+
+        for ((fname, ftype), options) in self.fields.iter().zip(self.options.iter()) {
+            if let Some(default) = &options.default {
+                encode_value(fname, ftype, default, options)?;
+            }
+        }
+
+        let parts = SchemaParts {
+            version: self.version,
             key: self.key,
+            key_fields: self.key_fields,
             fields: self.fields,
+            options: self.options,
+        };
+
+        Ok(parts.into())
+    }
+
+    /// Same as try_build, but panics if the schema is not valid
+    #[must_use]
+    pub fn build(self) -> Schema {
+        match self.try_build() {
+            Ok(schema) => schema,
+            Err(err) => panic!("{}", err),
         }
     }
 
     #[must_use]
-    pub fn add_field<S: ToString>(mut self, name: S, vtype: ValueType) -> Self {
+    pub fn add_field<S: ToString>(self, name: S, vtype: ValueType) -> Self {
+        self.add_field_with_options(name, vtype, FieldOptions::default())
+    }
+
+    /// Adds a field that can be set to `Value::Null`
+    #[must_use]
+    pub fn add_nullable_field<S: ToString>(self, name: S, vtype: ValueType) -> Self {
+        let options = FieldOptions {
+            nullable: true,
+            ..Default::default()
+        };
+
+        self.add_field_with_options(name, vtype, options)
+    }
+
+    /// Adds a field that is set to `default` if an entry does not specify it
+    #[must_use]
+    pub fn add_field_with_default<S: ToString, V: Into<Value>>(
+        self,
+        name: S,
+        vtype: ValueType,
+        default: V,
+    ) -> Self {
+        let options = FieldOptions {
+            default: Some(default.into()),
+            ..Default::default()
+        };
+
+        self.add_field_with_options(name, vtype, options)
+    }
+
+    /// Adds a field that is computed from the other fields whenever an entry is built
+    /// Generated fields are visible to all generators of fields that come after them.
+    #[must_use]
+    pub fn add_generated_field<S, F>(self, name: S, vtype: ValueType, func: F) -> Self
+    where
+        S: ToString,
+        F: Fn(&HashMap<String, Value>) -> Value + Send + Sync + 'static,
+    {
+        let options = FieldOptions {
+            generator: Some(Generator::new(func)),
+            ..Default::default()
+        };
+
+        self.add_field_with_options(name, vtype, options)
+    }
+
+    /// Marks `old_name` as a previous name of `name`, so that entries written
+    /// with an older version of the schema can be migrated
+    #[must_use]
+    pub fn add_alias<S: ToString>(mut self, name: &str, old_name: S) -> Self {
+        let Some(pos) = self.fields.iter().position(|(fname, _)| fname == name) else {
+            panic!("No such field: {}", name);
+        };
+
+        self.options[pos].aliases.push(old_name.to_string());
+        self
+    }
+
+    #[must_use]
+    pub fn add_field_with_options<S: ToString>(
+        mut self,
+        name: S,
+        vtype: ValueType,
+        options: FieldOptions,
+    ) -> Self {
         let name = name.to_string();
 
         for (fname, _) in self.fields.iter() {
@@ -36,56 +246,186 @@ impl SchemaBuilder {
         }
 
         self.fields.push((name, vtype));
+        self.options.push(options);
 
         self
     }
 }
 
 pub struct EntryBuilder<'a> {
-    fields: HashMap<&'a str, Vec<u8>>,
-    schema: &'a FieldTypeList,
+    /// The encoded value of each field, or None if it is null
+    fields: HashMap<&'a str, Option<Vec<u8>>>,
+    /// Fields whose value could not be encoded, which are reported in `errors`
+    invalid_fields: HashSet<&'a str>,
+    /// Names that are not part of the schema
+    unknown_fields: HashSet<&'a str>,
+    errors: Vec<SchemaError>,
+    schema: &'a Schema,
 }
 
 impl<'a> EntryBuilder<'a> {
-    pub(crate) fn new(schema: &'a FieldTypeList) -> Self {
+    pub(crate) fn new(schema: &'a Schema) -> Self {
         Self {
             fields: HashMap::new(),
+            invalid_fields: HashSet::new(),
+            unknown_fields: HashSet::new(),
+            errors: Vec::new(),
             schema,
         }
     }
 
+    /// Encodes a value for the field `name` using `encode`
+    /// Errors and unknown fields are not reported here, but when the entry is built.
+    fn encode_field<F>(&mut self, name: &'a str, encode: F)
+    where
+        F: FnOnce(&ValueType, &FieldOptions) -> Result<Option<Vec<u8>>, SchemaError>,
+    {
+        let Some(pos) = self.schema.position(name) else {
+            self.unknown_fields.insert(name);
+            return;
+        };
+
+        match encode(&self.schema.fields[pos].1, self.schema.field_options(pos)) {
+            Ok(bytes) => {
+                self.fields.insert(name, bytes);
+            }
+            Err(err) => {
+                self.invalid_fields.insert(name);
+                self.errors.push(err);
+            }
+        }
+    }
+
     #[must_use]
-    pub fn set_field<T: Serialize>(mut self, name: &'a str, value: &T) -> Self {
-        //TODO typecheck here
+    pub fn set_field<T: Serialize + HasValueType>(mut self, name: &'a str, value: &T) -> Self {
+        self.encode_field(name, |ftype, options| {
+            let vtype = T::value_type();
+            let bytes = bincode::serialize(value).map_err(|_| SchemaError::EncodingError)?;
 
-        let bytes = bincode::serialize(value).unwrap();
-        self.fields.insert(name, bytes);
+            // Checked and converted the same way as by set_field_from_value,
+            // e.g., when writing an i32 to an I64 field
+            let value =
+                Value::from_bytes(&bytes, &vtype).map_err(|_| SchemaError::EncodingError)?;
+            encode_value(name, ftype, &value, options)
+        });
 
         self
     }
 
     #[must_use]
     pub fn set_field_from_value(mut self, name: &'a str, value: &Value) -> Self {
-        //TODO typecheck here
-
-        let bytes = value.serialize_inner();
-        self.fields.insert(name, bytes);
+        self.encode_field(name, |ftype, options| {
+            encode_value(name, ftype, value, options)
+        });
 
         self
     }
 
-    #[must_use]
-    pub fn build(mut self) -> DataEntry {
-        let mut fields = Vec::new();
+    /// Creates the entry, or reports every missing field, unknown field
+    /// and invalid value at once
+    pub fn try_build(mut self) -> Result<DataEntry, SchemaError> {
+        let mut fields = Vec::with_capacity(self.schema.fields.len());
+        let mut error = EntryError {
+            invalid_values: self.errors,
+            ..Default::default()
+        };
+
+        let mut generated = Vec::new();
+
+        for (pos, (fname, ftype)) in self.schema.fields.iter().enumerate() {
+            let options = self.schema.field_options(pos);
+
+            let value = match self.fields.remove(fname.as_str()) {
+                Some(_) if options.generator.is_some() => {
+                    error
+                        .invalid_values
+                        .push(SchemaError::GeneratedField(fname.clone()));
+                    None
+                }
+                Some(value) => value,
+                // The field was set, but its value was invalid
+                None if self.invalid_fields.contains(fname.as_str()) => None,
+                None if options.generator.is_some() => {
+                    generated.push(pos);
+                    None
+                }
+                None => {
+                    // Omitted nullable fields without a default are set to null
+                    if let Some(default) = &options.default {
+                        match encode_value(fname, ftype, default, options) {
+                            Ok(bytes) => bytes,
+                            Err(err) => {
+                                error.invalid_values.push(err);
+                                None
+                            }
+                        }
+                    } else if options.nullable {
+                        None
+                    } else {
+                        error.missing_fields.push(fname.clone());
+                        None
+                    }
+                }
+            };
 
-        for (fname, _ftype) in self.schema.iter() {
-            let val = self
-                .fields
-                .remove(fname.as_str())
-                .expect("Field is missing");
-            fields.push(val);
+            fields.push(match value {
+                Some(bytes) => (bytes, false),
+                None => (null_slot(ftype), true),
+            });
         }
 
-        DataEntry { fields }
+        error.unknown_fields = self
+            .unknown_fields
+            .into_iter()
+            .map(str::to_string)
+            .collect();
+        error.unknown_fields.sort();
+
+        if !error.is_empty() {
+            return Err(SchemaError::InvalidEntry(error));
+        }
+
+        let mut entry = DataEntry::from_parts(self.schema.version, fields)?;
+
+        if !generated.is_empty() {
+            let mut inputs = HashMap::new();
+
+            for (pos, (fname, ftype)) in self.schema.fields.iter().enumerate() {
+                if !generated.contains(&pos) {
+                    inputs.insert(fname.clone(), read_field(&entry, pos, ftype)?);
+                }
+            }
+
+            for pos in generated {
+                let (fname, ftype) = &self.schema.fields[pos];
+                let options = self.schema.field_options(pos);
+                let value = options.generator.as_ref().unwrap().generate(&inputs);
+
+                match encode_value(fname, ftype, &value, options) {
+                    Ok(bytes) => write_field(&mut entry, pos, ftype, bytes)?,
+                    Err(err) => {
+                        error.invalid_values.push(err);
+                        continue;
+                    }
+                }
+
+                inputs.insert(fname.clone(), value);
+            }
+        }
+
+        if error.is_empty() {
+            Ok(entry)
+        } else {
+            Err(SchemaError::InvalidEntry(error))
+        }
+    }
+
+    /// Same as try_build, but panics if the entry is not valid
+    #[must_use]
+    pub fn build(self) -> DataEntry {
+        match self.try_build() {
+            Ok(entry) => entry,
+            Err(err) => panic!("{}", err),
+        }
     }
 }
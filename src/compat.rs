@@ -0,0 +1,279 @@
+use crate::{FieldTypeList, Schema, ValueType};
+
+/// A change between two versions of a schema that prevents
+/// one of them from reading entries written with the other
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BreakingChange {
+    KeyTypeChanged {
+        old: ValueType,
+        new: ValueType,
+    },
+    /// The components of a composite key changed
+    KeyFieldsChanged {
+        old: FieldTypeList,
+        new: FieldTypeList,
+    },
+    /// A field without a default value was added
+    FieldAdded(String),
+    /// A field without a default value was removed
+    FieldRemoved(String),
+    /// The type of a field changed in a way that cannot be widened
+    TypeChanged {
+        field: String,
+        old: ValueType,
+        new: ValueType,
+    },
+    /// The field is nullable when written but not when read
+    NullabilityChanged(String),
+    /// The field moved relative to other fields
+    FieldReordered(String),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompatibilityLevel {
+    /// Old and new readers can read entries written with either schema
+    Full,
+    /// New readers can read entries written with the old schema
+    Backward,
+    /// Old readers can read entries written with the new schema
+    Forward,
+    None,
+}
+
+/// The result of comparing two versions of a schema
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Compatibility {
+    /// Changes that prevent the new schema from reading old entries
+    pub backward: Vec<BreakingChange>,
+    /// Changes that prevent the old schema from reading new entries
+    pub forward: Vec<BreakingChange>,
+}
+
+impl Compatibility {
+    pub fn is_backward_compatible(&self) -> bool {
+        self.backward.is_empty()
+    }
+
+    pub fn is_forward_compatible(&self) -> bool {
+        self.forward.is_empty()
+    }
+
+    pub fn is_fully_compatible(&self) -> bool {
+        self.is_backward_compatible() && self.is_forward_compatible()
+    }
+
+    pub fn level(&self) -> CompatibilityLevel {
+        match (self.is_backward_compatible(), self.is_forward_compatible()) {
+            (true, true) => CompatibilityLevel::Full,
+            (true, false) => CompatibilityLevel::Backward,
+            (false, true) => CompatibilityLevel::Forward,
+            (false, false) => CompatibilityLevel::None,
+        }
+    }
+}
+
+/// Finds the position of the writer's field that the reader's field
+/// at `pos` would be migrated from
+pub(crate) fn find_source_field(reader: &Schema, pos: usize, writer: &Schema) -> Option<usize> {
+    let fname = &reader.fields[pos].0;
+    let aliases = &reader.field_options(pos).aliases;
+
+    writer
+        .fields
+        .iter()
+        .position(|(name, _)| name == fname || aliases.contains(name))
+}
+
+/// Collects the changes that prevent `reader` from migrating entries written with `writer`
+fn check_read(reader: &Schema, writer: &Schema, backward: bool) -> Vec<BreakingChange> {
+    let mut changes = Vec::new();
+
+    for (pos, (fname, ftype)) in reader.fields.iter().enumerate() {
+        let options = reader.field_options(pos);
+
+        if options.generator.is_some() {
+            continue;
+        }
+
+        let Some(writer_pos) = find_source_field(reader, pos, writer) else {
+            if options.default.is_none() && !options.nullable {
+                if backward {
+                    changes.push(BreakingChange::FieldAdded(fname.clone()));
+                } else {
+                    changes.push(BreakingChange::FieldRemoved(fname.clone()));
+                }
+            }
+            continue;
+        };
+
+        let writer_type = &writer.fields[writer_pos].1;
+
+        if !writer_type.can_widen_to(ftype) {
+            let (old, new) = if backward {
+                (writer_type.clone(), ftype.clone())
+            } else {
+                (ftype.clone(), writer_type.clone())
+            };
+
+            let field = if backward {
+                fname.clone()
+            } else {
+                writer.fields[writer_pos].0.clone()
+            };
+
+            changes.push(BreakingChange::TypeChanged { field, old, new });
+        }
+
+        if writer.field_options(writer_pos).nullable && !options.nullable {
+            changes.push(BreakingChange::NullabilityChanged(fname.clone()));
+        }
+    }
+
+    changes
+}
+
+impl Schema {
+    /// Checks whether this schema can replace `old`, following the
+    /// compatibility rules of Avro
+    ///
+    /// Entries are migrated field by field as described in `Schema::migrate`,
+    /// so fields may be added or removed as long as the reader has a default
+    /// for them, and types may be widened. Changes to the key type and the
+    /// relative order of fields are always breaking.
+    pub fn check_compatibility(&self, old: &Schema) -> Compatibility {
+        let mut result = Compatibility {
+            backward: check_read(self, old, true),
+            forward: check_read(old, self, false),
+        };
+
+        let key_change = if self.key_fields != old.key_fields {
+            Some(BreakingChange::KeyFieldsChanged {
+                old: old.key_fields.clone(),
+                new: self.key_fields.clone(),
+            })
+        } else if self.key != old.key {
+            Some(BreakingChange::KeyTypeChanged {
+                old: old.key.clone(),
+                new: self.key.clone(),
+            })
+        } else {
+            None
+        };
+
+        if let Some(change) = key_change {
+            result.backward.insert(0, change.clone());
+            result.forward.insert(0, change);
+        }
+
+        let mut last_pos = None;
+
+        for pos in 0..self.fields.len() {
+            let Some(old_pos) = find_source_field(self, pos, old) else {
+                continue;
+            };
+
+            if last_pos.is_some_and(|last| old_pos < last) {
+                let change = BreakingChange::FieldReordered(self.fields[pos].0.clone());
+                result.backward.push(change.clone());
+                result.forward.push(change);
+            } else {
+                last_pos = Some(old_pos);
+            }
+        }
+
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{BreakingChange, CompatibilityLevel};
+    use crate::{SchemaBuilder, ValueType};
+
+    #[test]
+    fn compatible_changes() {
+        let old = SchemaBuilder::new(ValueType::U64)
+            .add_field("count", ValueType::I64)
+            .add_field_with_default("label", ValueType::String, "")
+            .build();
+
+        let new = SchemaBuilder::new(ValueType::U64)
+            .add_field("count", ValueType::I64)
+            .add_nullable_field("comment", ValueType::String)
+            .build();
+
+        let result = new.check_compatibility(&old);
+        assert_eq!(result.level(), CompatibilityLevel::Full);
+    }
+
+    #[test]
+    fn appended_labels() {
+        let labels = |labels: &[&str]| labels.iter().map(|l| l.to_string()).collect();
+
+        let old = SchemaBuilder::new(ValueType::U64)
+            .add_field("status", ValueType::Enum(labels(&["active", "closed"])))
+            .build();
+
+        let new = SchemaBuilder::new(ValueType::U64)
+            .add_field(
+                "status",
+                ValueType::Enum(labels(&["active", "closed", "pending"])),
+            )
+            .build();
+
+        // Old entries can be read, but new entries may contain unknown labels
+        let result = new.check_compatibility(&old);
+        assert_eq!(result.level(), CompatibilityLevel::Backward);
+
+        let entry = old
+            .build_entry()
+            .set_field_from_value("status", &"closed".into())
+            .build();
+        assert_eq!(new.get_field(&entry, "status").unwrap(), "closed".into());
+    }
+
+    #[test]
+    fn breaking_changes() {
+        let old = SchemaBuilder::new(ValueType::U64)
+            .add_field("count", ValueType::I64)
+            .add_field("label", ValueType::String)
+            .add_field("flag", ValueType::Bool)
+            .build();
+
+        let new = SchemaBuilder::new(ValueType::I64)
+            .add_field("flag", ValueType::Bool)
+            .add_field("count", ValueType::F64)
+            .add_field("extra", ValueType::U64)
+            .build();
+
+        let result = new.check_compatibility(&old);
+        assert_eq!(result.level(), CompatibilityLevel::None);
+
+        let key_change = BreakingChange::KeyTypeChanged {
+            old: ValueType::U64,
+            new: ValueType::I64,
+        };
+
+        assert_eq!(
+            result.backward,
+            vec![
+                key_change.clone(),
+                BreakingChange::FieldAdded("extra".to_string()),
+                BreakingChange::FieldReordered("count".to_string()),
+            ]
+        );
+        assert_eq!(
+            result.forward,
+            vec![
+                key_change,
+                BreakingChange::TypeChanged {
+                    field: "count".to_string(),
+                    old: ValueType::I64,
+                    new: ValueType::F64,
+                },
+                BreakingChange::FieldRemoved("label".to_string()),
+                BreakingChange::FieldReordered("count".to_string()),
+            ]
+        );
+    }
+}
@@ -0,0 +1,778 @@
+//! Date and time values, with ISO-8601 parsing and formatting
+//!
+//! All types use the proleptic Gregorian calendar and ignore leap seconds.
+//! Timestamps are always stored relative to the Unix epoch in UTC; the timezone
+//! of a timestamp field only affects how its values are formatted.
+
+use std::cmp::Ordering;
+
+use serde::{Deserialize, Serialize};
+
+use crate::SchemaError;
+
+const NANOS_PER_SECOND: i64 = 1_000_000_000;
+const SECONDS_PER_DAY: i64 = 86_400;
+const NANOS_PER_DAY: i64 = SECONDS_PER_DAY * NANOS_PER_SECOND;
+
+/// Dates are stored as i32 days, which cannot reach beyond this year in either direction
+const MAX_YEAR: i64 = 6_000_000;
+
+/// The precision of a timestamp
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum TimeUnit {
+    Seconds,
+    Milliseconds,
+    Microseconds,
+    Nanoseconds,
+}
+
+impl TimeUnit {
+    pub fn ticks_per_second(&self) -> i64 {
+        match self {
+            Self::Seconds => 1,
+            Self::Milliseconds => 1_000,
+            Self::Microseconds => 1_000_000,
+            Self::Nanoseconds => NANOS_PER_SECOND,
+        }
+    }
+
+    /// Number of fractional digits needed to print a second at this precision
+    fn digits(&self) -> usize {
+        match self {
+            Self::Seconds => 0,
+            Self::Milliseconds => 3,
+            Self::Microseconds => 6,
+            Self::Nanoseconds => 9,
+        }
+    }
+
+    /// The SI symbol of the unit
+    fn symbol(&self) -> &'static str {
+        match self {
+            Self::Seconds => "s",
+            Self::Milliseconds => "ms",
+            Self::Microseconds => "us",
+            Self::Nanoseconds => "ns",
+        }
+    }
+
+    /// The coarsest unit that can represent `digits` fractional digits
+    fn from_digits(digits: usize) -> Self {
+        match digits {
+            0 => Self::Seconds,
+            1..=3 => Self::Milliseconds,
+            4..=6 => Self::Microseconds,
+            _ => Self::Nanoseconds,
+        }
+    }
+}
+
+fn parse_error(input: &str) -> SchemaError {
+    SchemaError::ParseError(input.to_string())
+}
+
+/// Days since 1970-01-01 for the given date
+fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = if year >= 0 { year } else { year - 399 } / 400;
+    let year_of_era = year - era * 400;
+    let month_from_march = (month as i64 + 9) % 12;
+    let day_of_year = (153 * month_from_march + 2) / 5 + day as i64 - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+
+    era * 146_097 + day_of_era - 719_468
+}
+
+/// The inverse of days_from_civil
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let days = days + 719_468;
+    let era = if days >= 0 { days } else { days - 146_096 } / 146_097;
+    let day_of_era = days - era * 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_from_march = (5 * day_of_year + 2) / 153;
+    let day = (day_of_year - (153 * month_from_march + 2) / 5 + 1) as u32;
+    let month = if month_from_march < 10 {
+        month_from_march + 3
+    } else {
+        month_from_march - 9
+    } as u32;
+    let year = year_of_era + era * 400;
+
+    (if month <= 2 { year + 1 } else { year }, month, day)
+}
+
+fn is_leap_year(year: i64) -> bool {
+    year % 4 == 0 && (year % 100 != 0 || year % 400 == 0)
+}
+
+fn days_in_month(year: i64, month: u32) -> u32 {
+    match month {
+        2 if is_leap_year(year) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// Minimal cursor for the fixed formats of ISO-8601
+struct Parser<'a> {
+    input: &'a str,
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn new(input: &'a str) -> Self {
+        Self { input, pos: 0 }
+    }
+
+    fn error(&self) -> SchemaError {
+        parse_error(self.input)
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.input.as_bytes().get(self.pos).copied()
+    }
+
+    fn eat(&mut self, c: u8) -> bool {
+        if self.peek() == Some(c) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, c: u8) -> Result<(), SchemaError> {
+        if self.eat(c) {
+            Ok(())
+        } else {
+            Err(self.error())
+        }
+    }
+
+    fn is_done(&self) -> bool {
+        self.pos == self.input.len()
+    }
+
+    fn finish(&self) -> Result<(), SchemaError> {
+        if self.is_done() {
+            Ok(())
+        } else {
+            Err(self.error())
+        }
+    }
+
+    /// Reads at least `min` decimal digits
+    fn digits(&mut self, min: usize) -> Result<(i64, usize), SchemaError> {
+        let mut value: i64 = 0;
+        let mut count = 0;
+
+        while let Some(c) = self.peek().filter(u8::is_ascii_digit) {
+            value = value
+                .checked_mul(10)
+                .and_then(|v| v.checked_add((c - b'0') as i64))
+                .ok_or_else(|| self.error())?;
+            count += 1;
+            self.pos += 1;
+        }
+
+        if count < min {
+            Err(self.error())
+        } else {
+            Ok((value, count))
+        }
+    }
+
+    /// Reads exactly `count` decimal digits
+    fn fixed(&mut self, count: usize) -> Result<i64, SchemaError> {
+        let start = self.pos;
+        let (value, _) = self.digits(count)?;
+
+        if self.pos - start != count {
+            Err(self.error())
+        } else {
+            Ok(value)
+        }
+    }
+
+    /// Reads an optional fraction of a second, returning nanoseconds and
+    /// the number of digits
+    fn fraction(&mut self) -> Result<(i64, usize), SchemaError> {
+        if !self.eat(b'.') && !self.eat(b',') {
+            return Ok((0, 0));
+        }
+
+        let start = self.pos;
+        let (_, count) = self.digits(1)?;
+        if count > 9 {
+            return Err(self.error());
+        }
+
+        let digits = &self.input[start..self.pos];
+        let nanos: i64 = format!("{:0<9}", digits).parse().unwrap();
+        Ok((nanos, count))
+    }
+
+    fn date(&mut self) -> Result<Date, SchemaError> {
+        let negative = self.eat(b'-');
+        if !negative {
+            self.eat(b'+');
+        }
+
+        let (year, _) = self.digits(4)?;
+        self.expect(b'-')?;
+        let month = self.fixed(2)?;
+        self.expect(b'-')?;
+        let day = self.fixed(2)?;
+
+        let year = if negative { -year } else { year };
+        Date::from_ymd(year, month as u32, day as u32).ok_or_else(|| self.error())
+    }
+
+    /// Returns the time and the number of fractional digits
+    fn time(&mut self) -> Result<(Time, usize), SchemaError> {
+        let hour = self.fixed(2)?;
+        self.expect(b':')?;
+        let minute = self.fixed(2)?;
+        self.expect(b':')?;
+        let second = self.fixed(2)?;
+        let (nanos, digits) = self.fraction()?;
+
+        let time = Time::from_hms_nano(hour as u32, minute as u32, second as u32, nanos as u32)
+            .ok_or_else(|| self.error())?;
+        Ok((time, digits))
+    }
+
+    /// Reads an offset such as `Z`, `+02:00` or `-0530` in seconds
+    fn offset(&mut self) -> Result<i64, SchemaError> {
+        if self.eat(b'Z') {
+            return Ok(0);
+        }
+
+        let sign = if self.eat(b'+') {
+            1
+        } else if self.eat(b'-') {
+            -1
+        } else {
+            return Err(self.error());
+        };
+
+        let hours = self.fixed(2)?;
+        self.eat(b':');
+        let minutes = self.fixed(2)?;
+
+        if hours > 23 || minutes > 59 {
+            return Err(self.error());
+        }
+
+        Ok(sign * (hours * 3600 + minutes * 60))
+    }
+}
+
+/// Parses the timezone of a timestamp field, returning its offset from UTC in seconds
+///
+/// Only fixed offsets, such as `+02:00`, and `UTC` are supported.
+pub fn parse_timezone(timezone: &str) -> Result<i64, SchemaError> {
+    if timezone == "UTC" {
+        return Ok(0);
+    }
+
+    let mut parser = Parser::new(timezone);
+    let offset = parser.offset()?;
+    parser.finish()?;
+    Ok(offset)
+}
+
+fn format_fraction(fmt: &mut std::fmt::Formatter, nanos: i64, digits: usize) -> std::fmt::Result {
+    if digits > 0 {
+        let fraction = format!("{:09}", nanos);
+        write!(fmt, ".{}", &fraction[..digits])?;
+    }
+
+    Ok(())
+}
+
+/// A calendar date, stored as days since 1970-01-01
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct Date(i32);
+
+impl Date {
+    pub fn from_days(days: i32) -> Self {
+        Self(days)
+    }
+
+    /// Returns None if the date does not exist or is out of range
+    pub fn from_ymd(year: i64, month: u32, day: u32) -> Option<Self> {
+        if year.abs() > MAX_YEAR {
+            return None;
+        }
+
+        if !(1..=12).contains(&month) || day < 1 || day > days_in_month(year, month) {
+            return None;
+        }
+
+        days_from_civil(year, month, day).try_into().ok().map(Self)
+    }
+
+    pub fn days(&self) -> i32 {
+        self.0
+    }
+
+    /// The year, month and day of this date
+    pub fn to_ymd(&self) -> (i64, u32, u32) {
+        civil_from_days(self.0 as i64)
+    }
+
+    /// Parses a date of the form `YYYY-MM-DD`
+    pub fn parse(input: &str) -> Result<Self, SchemaError> {
+        let mut parser = Parser::new(input);
+        let date = parser.date()?;
+        parser.finish()?;
+        Ok(date)
+    }
+}
+
+impl std::fmt::Display for Date {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let (year, month, day) = self.to_ymd();
+
+        if year < 0 {
+            write!(fmt, "-{:04}-{:02}-{:02}", -year, month, day)
+        } else {
+            write!(fmt, "{:04}-{:02}-{:02}", year, month, day)
+        }
+    }
+}
+
+/// A time of day, stored as nanoseconds since midnight
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct Time(i64);
+
+impl Time {
+    /// Returns None if the nanoseconds are not within a single day
+    pub fn from_nanos(nanos: i64) -> Option<Self> {
+        (0..NANOS_PER_DAY).contains(&nanos).then_some(Self(nanos))
+    }
+
+    pub fn from_hms_nano(hour: u32, minute: u32, second: u32, nanos: u32) -> Option<Self> {
+        if hour > 23 || minute > 59 || second > 59 || nanos as i64 >= NANOS_PER_SECOND {
+            return None;
+        }
+
+        let seconds = (hour * 3600 + minute * 60 + second) as i64;
+        Some(Self(seconds * NANOS_PER_SECOND + nanos as i64))
+    }
+
+    pub fn nanos(&self) -> i64 {
+        self.0
+    }
+
+    /// The hour, minute, second and nanosecond of this time
+    pub fn to_hms_nano(&self) -> (u32, u32, u32, u32) {
+        let seconds = self.0 / NANOS_PER_SECOND;
+        let nanos = self.0 % NANOS_PER_SECOND;
+
+        (
+            (seconds / 3600) as u32,
+            (seconds / 60 % 60) as u32,
+            (seconds % 60) as u32,
+            nanos as u32,
+        )
+    }
+
+    /// Parses a time of the form `HH:MM:SS` with an optional fraction of a second
+    pub fn parse(input: &str) -> Result<Self, SchemaError> {
+        let mut parser = Parser::new(input);
+        let (time, _) = parser.time()?;
+        parser.finish()?;
+        Ok(time)
+    }
+
+    fn format(&self, fmt: &mut std::fmt::Formatter, digits: usize) -> std::fmt::Result {
+        let (hour, minute, second, nanos) = self.to_hms_nano();
+        write!(fmt, "{:02}:{:02}:{:02}", hour, minute, second)?;
+        format_fraction(fmt, nanos as i64, digits)
+    }
+}
+
+impl std::fmt::Display for Time {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let nanos = self.0 % NANOS_PER_SECOND;
+        let digits = if nanos == 0 {
+            0
+        } else if nanos % 1_000_000 == 0 {
+            3
+        } else if nanos % 1_000 == 0 {
+            6
+        } else {
+            9
+        };
+
+        self.format(fmt, digits)
+    }
+}
+
+/// Splits nanoseconds since the Unix epoch into a date and a time of day
+fn date_time_from_nanos(nanos: i128) -> Option<(Date, Time)> {
+    let days = nanos.div_euclid(NANOS_PER_DAY as i128).try_into().ok()?;
+    let time = nanos.rem_euclid(NANOS_PER_DAY as i128) as i64;
+
+    Some((Date(days), Time(time)))
+}
+
+/// A point in time, stored as ticks of the given unit since the Unix epoch (in UTC)
+///
+/// Timestamps with different units are compared by the point in time they represent.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct Timestamp {
+    value: i64,
+    unit: TimeUnit,
+}
+
+impl Timestamp {
+    pub fn new(value: i64, unit: TimeUnit) -> Self {
+        Self { value, unit }
+    }
+
+    pub fn from_date_time(date: Date, time: Time, unit: TimeUnit) -> Option<Self> {
+        let nanos = date.days() as i128 * NANOS_PER_DAY as i128 + time.nanos() as i128;
+        Self::from_nanos(nanos, unit)
+    }
+
+    /// Rounds towards negative infinity if `unit` is coarser than nanoseconds
+    fn from_nanos(nanos: i128, unit: TimeUnit) -> Option<Self> {
+        let divisor = (NANOS_PER_SECOND / unit.ticks_per_second()) as i128;
+        let value = nanos.div_euclid(divisor).try_into().ok()?;
+        Some(Self { value, unit })
+    }
+
+    pub fn value(&self) -> i64 {
+        self.value
+    }
+
+    pub fn unit(&self) -> TimeUnit {
+        self.unit
+    }
+
+    pub fn as_nanos(&self) -> i128 {
+        self.value as i128 * (NANOS_PER_SECOND / self.unit.ticks_per_second()) as i128
+    }
+
+    /// Converts the timestamp to another unit
+    ///
+    /// Converting to a coarser unit rounds towards negative infinity.
+    /// Returns None if the result does not fit into the new unit.
+    pub fn to_unit(&self, unit: TimeUnit) -> Option<Self> {
+        if unit == self.unit {
+            Some(*self)
+        } else {
+            Self::from_nanos(self.as_nanos(), unit)
+        }
+    }
+
+    /// The date and time of this timestamp, in UTC
+    pub fn to_date_time(&self) -> Option<(Date, Time)> {
+        date_time_from_nanos(self.as_nanos())
+    }
+
+    /// Parses a timestamp of the form `YYYY-MM-DDTHH:MM:SS[.fraction][offset]`
+    ///
+    /// Timestamps without an offset are assumed to be in UTC. The unit of the
+    /// result is the coarsest unit that can hold the given fraction of a second.
+    pub fn parse(input: &str) -> Result<Self, SchemaError> {
+        let mut parser = Parser::new(input);
+        let date = parser.date()?;
+
+        if !parser.eat(b'T') && !parser.eat(b' ') {
+            return Err(parse_error(input));
+        }
+
+        let (time, digits) = parser.time()?;
+        let offset = if parser.is_done() {
+            0
+        } else {
+            parser.offset()?
+        };
+        parser.finish()?;
+
+        let nanos = date.days() as i128 * NANOS_PER_DAY as i128 + time.nanos() as i128
+            - offset as i128 * NANOS_PER_SECOND as i128;
+
+        Self::from_nanos(nanos, TimeUnit::from_digits(digits)).ok_or_else(|| parse_error(input))
+    }
+
+    /// Formats the timestamp in the given timezone, or in UTC if none is given
+    pub fn to_iso8601(&self, timezone: Option<&str>) -> Result<String, SchemaError> {
+        let offset = match timezone {
+            Some(tz) => parse_timezone(tz)?,
+            None => 0,
+        };
+
+        let local = self.as_nanos() + offset as i128 * NANOS_PER_SECOND as i128;
+
+        let Some((date, time)) = date_time_from_nanos(local) else {
+            return Err(SchemaError::OutOfRange("timestamp".to_string()));
+        };
+
+        let offset = if offset == 0 {
+            "Z".to_string()
+        } else {
+            let sign = if offset < 0 { '-' } else { '+' };
+            let offset = offset.abs();
+            format!("{}{:02}:{:02}", sign, offset / 3600, offset / 60 % 60)
+        };
+
+        Ok(format!(
+            "{}T{}{}",
+            date,
+            FormattedTime(time, self.unit.digits()),
+            offset
+        ))
+    }
+}
+
+struct FormattedTime(Time, usize);
+
+impl std::fmt::Display for FormattedTime {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+        self.0.format(fmt, self.1)
+    }
+}
+
+impl PartialEq for Timestamp {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_nanos() == other.as_nanos()
+    }
+}
+
+impl Eq for Timestamp {}
+
+impl PartialOrd for Timestamp {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Timestamp {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.as_nanos().cmp(&other.as_nanos())
+    }
+}
+
+impl std::fmt::Display for Timestamp {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+        // Timestamps beyond the supported years are printed as raw ticks
+        match self.to_iso8601(None) {
+            Ok(s) => write!(fmt, "{}", s),
+            Err(_) => write!(fmt, "{} {}", self.value, self.unit.symbol()),
+        }
+    }
+}
+
+/// A signed amount of time, stored in nanoseconds
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct Duration(i64);
+
+impl Duration {
+    pub fn from_nanos(nanos: i64) -> Self {
+        Self(nanos)
+    }
+
+    pub fn from_secs(seconds: i64) -> Option<Self> {
+        seconds.checked_mul(NANOS_PER_SECOND).map(Self)
+    }
+
+    pub fn nanos(&self) -> i64 {
+        self.0
+    }
+
+    /// Parses a duration of the form `PnDTnHnMn.nS`, with an optional leading minus
+    ///
+    /// Years, months and weeks are not supported as their length is ambiguous.
+    /// Every designator may appear at most once, in the order given above.
+    pub fn parse(input: &str) -> Result<Self, SchemaError> {
+        let mut parser = Parser::new(input);
+        let negative = parser.eat(b'-');
+        parser.expect(b'P')?;
+
+        let mut nanos: i128 = 0;
+        let mut in_time = false;
+        // The unit of the last designator, in seconds
+        let mut last_unit = None;
+
+        while !parser.is_done() {
+            if !in_time && parser.eat(b'T') {
+                // `T` must be followed by at least one time designator
+                if parser.is_done() {
+                    return Err(parse_error(input));
+                }
+
+                in_time = true;
+                continue;
+            }
+
+            let (value, _) = parser.digits(1)?;
+            let (fraction, _) = parser.fraction()?;
+            let value = value as i128;
+
+            let unit = match (in_time, parser.peek()) {
+                (false, Some(b'D')) => SECONDS_PER_DAY,
+                (true, Some(b'H')) => 3600,
+                (true, Some(b'M')) => 60,
+                (true, Some(b'S')) => 1,
+                _ => return Err(parse_error(input)),
+            };
+
+            if fraction != 0 && unit != 1 {
+                return Err(parse_error(input));
+            }
+
+            if last_unit.is_some_and(|last| unit >= last) {
+                return Err(parse_error(input));
+            }
+
+            parser.pos += 1;
+            last_unit = Some(unit);
+            nanos += value * unit as i128 * NANOS_PER_SECOND as i128 + fraction as i128;
+        }
+
+        if last_unit.is_none() {
+            return Err(parse_error(input));
+        }
+
+        if negative {
+            nanos = -nanos;
+        }
+
+        nanos.try_into().map(Self).map_err(|_| parse_error(input))
+    }
+}
+
+impl std::fmt::Display for Duration {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+        if self.0 < 0 {
+            write!(fmt, "-")?;
+        }
+        write!(fmt, "P")?;
+
+        let total = self.0.unsigned_abs();
+        let nanos = (total % NANOS_PER_SECOND as u64) as i64;
+        let seconds = total / NANOS_PER_SECOND as u64;
+        let days = seconds / SECONDS_PER_DAY as u64;
+        let (hours, minutes, seconds) = (seconds / 3600 % 24, seconds / 60 % 60, seconds % 60);
+
+        if days > 0 {
+            write!(fmt, "{}D", days)?;
+        }
+
+        if hours == 0 && minutes == 0 && seconds == 0 && nanos == 0 {
+            if days == 0 {
+                write!(fmt, "T0S")?;
+            }
+            return Ok(());
+        }
+
+        write!(fmt, "T")?;
+        if hours > 0 {
+            write!(fmt, "{}H", hours)?;
+        }
+        if minutes > 0 {
+            write!(fmt, "{}M", minutes)?;
+        }
+        if seconds > 0 || nanos > 0 {
+            write!(fmt, "{}", seconds)?;
+            if nanos > 0 {
+                let fraction = format!("{:09}", nanos);
+                write!(fmt, ".{}", fraction.trim_end_matches('0'))?;
+            }
+            write!(fmt, "S")?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Date, Duration, Time, TimeUnit, Timestamp};
+
+    #[test]
+    fn civil_dates() {
+        for (s, days) in [
+            ("1970-01-01", 0),
+            ("2000-02-29", 11_016),
+            ("1969-12-31", -1),
+            ("0001-01-01", -719_162),
+        ] {
+            let date = Date::parse(s).unwrap();
+            assert_eq!(date.days(), days);
+            assert_eq!(date.to_string(), s);
+        }
+
+        assert!(Date::parse("2001-02-29").is_err());
+        assert!(Date::parse("2001-1-01").is_err());
+        assert!(Date::parse("922337203685477580-01-01").is_err());
+        assert!(Date::parse("-922337203685477580-01-01").is_err());
+        assert!(Date::parse("5000000-01-01").is_ok());
+        assert!(Timestamp::parse("922337203685477580-01-01T00:00:00Z").is_err());
+    }
+
+    #[test]
+    fn timestamps() {
+        let ts = Timestamp::parse("2024-03-01T12:30:05.250+02:00").unwrap();
+        assert_eq!(ts.unit(), TimeUnit::Milliseconds);
+        assert_eq!(ts.to_iso8601(None).unwrap(), "2024-03-01T10:30:05.250Z");
+        assert_eq!(
+            ts.to_iso8601(Some("-05:30")).unwrap(),
+            "2024-03-01T05:00:05.250-05:30"
+        );
+
+        let distant = Timestamp::parse("3000-01-01T00:00:00Z").unwrap();
+        assert_eq!(distant.to_string(), "3000-01-01T00:00:00Z");
+
+        let before = Timestamp::parse("1960-05-01T00:00:00Z").unwrap();
+        assert!(before < ts);
+        assert_eq!(before.to_string(), "1960-05-01T00:00:00Z");
+
+        assert_eq!(
+            ts.to_unit(TimeUnit::Seconds).unwrap(),
+            Timestamp::parse("2024-03-01T10:30:05").unwrap()
+        );
+        assert_eq!(
+            Timestamp::new(-1, TimeUnit::Milliseconds)
+                .to_unit(TimeUnit::Seconds)
+                .unwrap()
+                .value(),
+            -1
+        );
+        assert!(Timestamp::new(i64::MAX, TimeUnit::Seconds)
+            .to_unit(TimeUnit::Nanoseconds)
+            .is_none());
+        assert_eq!(
+            Timestamp::new(i64::MAX, TimeUnit::Seconds).to_string(),
+            "9223372036854775807 s"
+        );
+    }
+
+    #[test]
+    fn times_and_durations() {
+        let time = Time::parse("23:59:01.5").unwrap();
+        assert_eq!(time.to_hms_nano(), (23, 59, 1, 500_000_000));
+        assert_eq!(time.to_string(), "23:59:01.500");
+        assert!(Time::parse("24:00:00").is_err());
+
+        let duration = Duration::parse("P1DT2H0.5S").unwrap();
+        assert_eq!(duration.nanos(), 93_600_500_000_000);
+        assert_eq!(duration.to_string(), "P1DT2H0.5S");
+        assert_eq!(Duration::parse("-PT90M").unwrap().to_string(), "-PT1H30M");
+        assert_eq!(Duration::from_nanos(0).to_string(), "PT0S");
+        assert!(Duration::parse("P").is_err());
+        assert!(Duration::parse("P1M").is_err());
+        assert!(Duration::parse("P1D1D").is_err());
+        assert!(Duration::parse("PT1S1H").is_err());
+        assert!(Duration::parse("PT1M1M").is_err());
+        assert!(Duration::parse("P1DT").is_err());
+    }
+}
@@ -0,0 +1,462 @@
+//! Fixed-point decimal numbers with exact arithmetic
+
+use std::cmp::Ordering;
+
+use serde::{Deserialize, Serialize};
+
+use crate::SchemaError;
+
+/// How to round a decimal that cannot be represented at the requested scale
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RoundingMode {
+    /// Towards zero
+    Down,
+    /// Away from zero
+    Up,
+    /// Towards negative infinity
+    Floor,
+    /// Towards positive infinity
+    Ceiling,
+    /// To the nearest neighbour, or away from zero if both are equally near
+    HalfUp,
+    /// To the nearest neighbour, or towards zero if both are equally near
+    HalfDown,
+    /// To the nearest neighbour, or to the even one if both are equally near
+    HalfEven,
+}
+
+fn pow10(exp: u8) -> Option<i128> {
+    10i128.checked_pow(exp as u32)
+}
+
+/// Divides `num` by `denom` and rounds the quotient to an integer
+fn round_quotient(num: i128, denom: i128, mode: RoundingMode) -> Option<i128> {
+    let quotient = num.checked_div(denom)?;
+    let remainder = num % denom;
+
+    if remainder == 0 {
+        return Some(quotient);
+    }
+
+    let positive = (num < 0) == (denom < 0);
+    let remainder = remainder.unsigned_abs();
+    let half = remainder.cmp(&(denom.unsigned_abs() - remainder));
+
+    let away = match mode {
+        RoundingMode::Down => false,
+        RoundingMode::Up => true,
+        RoundingMode::Floor => !positive,
+        RoundingMode::Ceiling => positive,
+        RoundingMode::HalfUp => half != Ordering::Less,
+        RoundingMode::HalfDown => half == Ordering::Greater,
+        RoundingMode::HalfEven => match half {
+            Ordering::Less => false,
+            Ordering::Equal => quotient % 2 != 0,
+            Ordering::Greater => true,
+        },
+    };
+
+    if !away {
+        Some(quotient)
+    } else if positive {
+        quotient.checked_add(1)
+    } else {
+        quotient.checked_sub(1)
+    }
+}
+
+/// A decimal number, stored as `mantissa * 10^-scale`
+///
+/// Decimals are compared by their numeric value, so `1.5` equals `1.50`.
+/// The scale is at most `MAX_PRECISION`.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(try_from = "DecimalParts")]
+pub struct Decimal {
+    mantissa: i128,
+    scale: u8,
+}
+
+/// The fields of a `Decimal`, before its scale has been checked
+#[derive(Deserialize)]
+#[serde(rename = "Decimal")]
+struct DecimalParts {
+    mantissa: i128,
+    scale: u8,
+}
+
+impl TryFrom<DecimalParts> for Decimal {
+    type Error = SchemaError;
+
+    fn try_from(parts: DecimalParts) -> Result<Self, SchemaError> {
+        Decimal::new(parts.mantissa, parts.scale).ok_or(SchemaError::EncodingError)
+    }
+}
+
+impl Decimal {
+    /// The largest number of digits a decimal column can hold
+    pub const MAX_PRECISION: u8 = 38;
+
+    /// Returns None if `scale` is larger than `MAX_PRECISION`
+    pub fn new(mantissa: i128, scale: u8) -> Option<Self> {
+        (scale <= Self::MAX_PRECISION).then_some(Self { mantissa, scale })
+    }
+
+    pub fn mantissa(&self) -> i128 {
+        self.mantissa
+    }
+
+    pub fn scale(&self) -> u8 {
+        self.scale
+    }
+
+    /// The number of digits needed to store this value at its scale
+    pub fn precision(&self) -> u8 {
+        let digits = match self.mantissa.unsigned_abs().checked_ilog10() {
+            Some(log) => log as u8 + 1,
+            None => 1,
+        };
+
+        digits.max(self.scale)
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.mantissa < 0
+    }
+
+    /// Changes the scale of the number, rounding if needed
+    /// Returns None if the result does not fit into 128 bits or the scale is too large.
+    pub fn rescale(&self, scale: u8, mode: RoundingMode) -> Option<Self> {
+        let mantissa = match scale.cmp(&self.scale) {
+            Ordering::Equal => self.mantissa,
+            Ordering::Greater => self.mantissa.checked_mul(pow10(scale - self.scale)?)?,
+            Ordering::Less => round_quotient(self.mantissa, pow10(self.scale - scale)?, mode)?,
+        };
+
+        Self::new(mantissa, scale)
+    }
+
+    /// Converts the number to `scale` if that does not require rounding
+    /// and the result has at most `precision` digits
+    pub fn fit_to(&self, precision: u8, scale: u8) -> Option<Self> {
+        let result = self.rescale(scale, RoundingMode::Down)?;
+
+        if result != *self || result.mantissa.unsigned_abs() >= pow10(precision)? as u128 {
+            None
+        } else {
+            Some(result)
+        }
+    }
+
+    /// The integer part and the fraction (in units of 10^-scale), both rounded towards zero
+    fn split(&self) -> (i128, i128) {
+        let unit = pow10(self.scale).expect("Scale is at most MAX_PRECISION");
+        (self.mantissa / unit, self.mantissa % unit)
+    }
+
+    /// Brings both numbers to the larger of their scales
+    fn align(&self, other: &Self) -> Option<(i128, i128, u8)> {
+        let scale = self.scale.max(other.scale);
+        let lhs = self.rescale(scale, RoundingMode::Down)?;
+        let rhs = other.rescale(scale, RoundingMode::Down)?;
+
+        Some((lhs.mantissa, rhs.mantissa, scale))
+    }
+
+    pub fn checked_add(&self, other: &Self) -> Option<Self> {
+        let (lhs, rhs, scale) = self.align(other)?;
+        Self::new(lhs.checked_add(rhs)?, scale)
+    }
+
+    pub fn checked_sub(&self, other: &Self) -> Option<Self> {
+        let (lhs, rhs, scale) = self.align(other)?;
+        Self::new(lhs.checked_sub(rhs)?, scale)
+    }
+
+    /// Returns None if the mantissa is `i128::MIN`, whose negation does not fit
+    pub fn checked_neg(&self) -> Option<Self> {
+        Self::new(self.mantissa.checked_neg()?, self.scale)
+    }
+
+    /// The scale of the product is the sum of both scales
+    pub fn checked_mul(&self, other: &Self) -> Option<Self> {
+        let scale = self.scale.checked_add(other.scale)?;
+        Self::new(self.mantissa.checked_mul(other.mantissa)?, scale)
+    }
+
+    /// Divides by `other`, rounding the result to `scale` digits
+    /// Returns None if `other` is zero or the result does not fit into 128 bits.
+    pub fn checked_div(&self, other: &Self, scale: u8, mode: RoundingMode) -> Option<Self> {
+        let exp = scale as i16 + other.scale as i16 - self.scale as i16;
+
+        let (num, denom) = if exp >= 0 {
+            (
+                self.mantissa.checked_mul(pow10(exp as u8)?)?,
+                other.mantissa,
+            )
+        } else {
+            (
+                self.mantissa,
+                other.mantissa.checked_mul(pow10((-exp) as u8)?)?,
+            )
+        };
+
+        if denom == 0 {
+            return None;
+        }
+
+        Self::new(round_quotient(num, denom, mode)?, scale)
+    }
+
+    /// Parses a number such as `-12.50` or `1.5E+3`
+    pub fn parse(input: &str) -> Result<Self, SchemaError> {
+        let error = || SchemaError::ParseError(input.to_string());
+
+        let (number, exp) = match input.find(['e', 'E']) {
+            Some(pos) => {
+                let exp: i64 = input[pos + 1..].parse().map_err(|_| error())?;
+                (&input[..pos], exp)
+            }
+            None => (input, 0),
+        };
+
+        let (negative, number) = match number.as_bytes().first() {
+            Some(b'-') => (true, &number[1..]),
+            Some(b'+') => (false, &number[1..]),
+            _ => (false, number),
+        };
+
+        let (integer, fraction) = number.split_once('.').unwrap_or((number, ""));
+        let digits = || integer.bytes().chain(fraction.bytes());
+
+        if integer.len() + fraction.len() == 0 || !digits().all(|c| c.is_ascii_digit()) {
+            return Err(error());
+        }
+
+        let mut mantissa: i128 = 0;
+        for digit in digits() {
+            mantissa = mantissa
+                .checked_mul(10)
+                .and_then(|m| m.checked_add((digit - b'0') as i128))
+                .ok_or_else(error)?;
+        }
+
+        if negative {
+            mantissa = -mantissa;
+        }
+
+        // The number of digits after the decimal point, once the exponent is applied
+        let scale = i64::try_from(fraction.len())
+            .ok()
+            .and_then(|len| len.checked_sub(exp))
+            .ok_or_else(error)?;
+
+        if scale < 0 {
+            let factor = u8::try_from(-scale)
+                .ok()
+                .and_then(pow10)
+                .ok_or_else(error)?;
+            mantissa = mantissa.checked_mul(factor).ok_or_else(error)?;
+            Ok(Self { mantissa, scale: 0 })
+        } else {
+            u8::try_from(scale)
+                .ok()
+                .and_then(|scale| Self::new(mantissa, scale))
+                .ok_or_else(error)
+        }
+    }
+}
+
+impl PartialEq for Decimal {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Decimal {}
+
+impl PartialOrd for Decimal {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Decimal {
+    fn cmp(&self, other: &Self) -> Ordering {
+        if let Some((lhs, rhs, _)) = self.align(other) {
+            return lhs.cmp(&rhs);
+        }
+
+        // One of the numbers is too large to be scaled up, so compare
+        // the signs, integer parts and fractions separately
+        let sign = self.mantissa.signum().cmp(&other.mantissa.signum());
+        if sign != Ordering::Equal {
+            return sign;
+        }
+
+        let (lhs_int, lhs_frac) = self.split();
+        let (rhs_int, rhs_frac) = other.split();
+
+        // Fractions are below one, so they fit into 128 bits at either scale
+        let scale = self.scale.max(other.scale);
+        let lhs_frac = lhs_frac * pow10(scale - self.scale).unwrap();
+        let rhs_frac = rhs_frac * pow10(scale - other.scale).unwrap();
+
+        lhs_int.cmp(&rhs_int).then_with(|| lhs_frac.cmp(&rhs_frac))
+    }
+}
+
+impl std::ops::Add for Decimal {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        self.checked_add(&other).expect("Decimal overflow")
+    }
+}
+
+impl std::ops::Sub for Decimal {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        self.checked_sub(&other).expect("Decimal overflow")
+    }
+}
+
+impl std::ops::Mul for Decimal {
+    type Output = Self;
+
+    fn mul(self, other: Self) -> Self {
+        self.checked_mul(&other).expect("Decimal overflow")
+    }
+}
+
+impl std::ops::Neg for Decimal {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        self.checked_neg().expect("Decimal overflow")
+    }
+}
+
+impl std::fmt::Display for Decimal {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let scale = self.scale as usize;
+        let digits = format!(
+            "{:0>width$}",
+            self.mantissa.unsigned_abs(),
+            width = scale + 1
+        );
+        let (integer, fraction) = digits.split_at(digits.len() - scale);
+
+        if self.is_negative() {
+            write!(fmt, "-")?;
+        }
+
+        if fraction.is_empty() {
+            write!(fmt, "{}", integer)
+        } else {
+            write!(fmt, "{}.{}", integer, fraction)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Decimal, RoundingMode};
+
+    fn dec(s: &str) -> Decimal {
+        Decimal::parse(s).unwrap()
+    }
+
+    #[test]
+    fn parse_and_format() {
+        for s in [
+            "0",
+            "-0.05",
+            "12.50",
+            "99999999999999999999999999999999999999",
+        ] {
+            assert_eq!(dec(s).to_string(), s);
+        }
+
+        assert_eq!(dec("1.5E+3").to_string(), "1500");
+        assert_eq!(dec("25e-3").to_string(), "0.025");
+        assert_eq!(dec(".5").to_string(), "0.5");
+        assert!(Decimal::parse("1.2.3").is_err());
+        assert!(Decimal::parse("-").is_err());
+        assert!(Decimal::parse("NaN").is_err());
+        assert!(Decimal::parse("1e-2147483648").is_err());
+        assert!(Decimal::parse("1e-9223372036854775808").is_err());
+        assert!(Decimal::parse("1e99999999999999999999").is_err());
+        assert!(Decimal::parse("1e-39").is_err());
+    }
+
+    #[test]
+    fn arithmetic() {
+        assert_eq!(dec("0.1") + dec("0.2"), dec("0.3"));
+        assert_eq!((dec("1.25") - dec("3")).to_string(), "-1.75");
+        assert_eq!((dec("1.5") * dec("-0.25")).to_string(), "-0.375");
+        assert_eq!(
+            dec("1")
+                .checked_div(&dec("3"), 4, RoundingMode::HalfEven)
+                .unwrap()
+                .to_string(),
+            "0.3333"
+        );
+        assert_eq!(dec("1").checked_div(&dec("0"), 2, RoundingMode::Down), None);
+        assert!(dec("1.5") > dec("1.49"));
+        assert_eq!(dec("1.5"), dec("1.500"));
+        assert_eq!(dec("1").checked_mul(&dec("1e-38")).unwrap(), dec("1e-38"));
+        assert_eq!(dec("0.1").checked_mul(&dec("1e-38")), None);
+        assert_eq!(-dec("-1.5"), dec("1.5"));
+        assert_eq!(Decimal::new(i128::MIN, 2).unwrap().checked_neg(), None);
+    }
+
+    #[test]
+    fn compare() {
+        // Scale differences that are too large to align
+        let tiny = Decimal::new(1, 38).unwrap();
+        let huge = dec("99999999999999999999999999999999999999");
+
+        assert!(Decimal::new(0, 0).unwrap() < tiny);
+        assert!(-tiny < Decimal::new(0, 0).unwrap());
+        assert!(tiny < huge);
+        assert!(-huge < -tiny);
+        assert!(dec("2") > Decimal::new(15, 38).unwrap());
+        assert!(dec("-2") < Decimal::new(-15, 38).unwrap());
+        assert!(dec("2") < Decimal::new(i128::MAX, 37).unwrap());
+        assert!(dec("-2") > Decimal::new(i128::MIN, 37).unwrap());
+        assert!(dec("17.1") > Decimal::new(i128::MAX, 37).unwrap());
+        assert!(dec("-17.1") < Decimal::new(i128::MIN, 37).unwrap());
+
+        assert_eq!(Decimal::new(1, 39), None);
+    }
+
+    #[test]
+    fn rounding() {
+        let cases = [
+            (RoundingMode::Down, ["2", "2", "-2", "-2"]),
+            (RoundingMode::Up, ["3", "3", "-3", "-3"]),
+            (RoundingMode::Floor, ["2", "2", "-3", "-3"]),
+            (RoundingMode::Ceiling, ["3", "3", "-2", "-2"]),
+            (RoundingMode::HalfUp, ["3", "3", "-3", "-3"]),
+            (RoundingMode::HalfDown, ["2", "3", "-2", "-3"]),
+            (RoundingMode::HalfEven, ["2", "3", "-2", "-3"]),
+        ];
+
+        for (mode, expected) in cases {
+            for (input, expected) in ["2.5", "2.51", "-2.5", "-2.51"].iter().zip(expected) {
+                assert_eq!(
+                    dec(input).rescale(0, mode).unwrap().to_string(),
+                    expected,
+                    "{} {:?}",
+                    input,
+                    mode
+                );
+            }
+        }
+
+        assert_eq!(dec("12.345").fit_to(5, 2), None);
+        assert_eq!(dec("123.4").fit_to(4, 2), None);
+        assert_eq!(dec("12.3").fit_to(4, 2).unwrap().mantissa(), 1230);
+    }
+}
@@ -0,0 +1,224 @@
+use crate::compat::find_source_field;
+use crate::{FieldOptions, FieldTypeList, Schema, Value, ValueType};
+
+/// A single step needed to turn one schema into another
+#[derive(Debug, Clone, PartialEq)]
+pub enum SchemaOp {
+    ChangeKeyType {
+        old: ValueType,
+        new: ValueType,
+    },
+    ChangeKeyFields {
+        old: FieldTypeList,
+        new: FieldTypeList,
+    },
+    AddField {
+        name: String,
+        vtype: ValueType,
+        options: FieldOptions,
+    },
+    DropField {
+        name: String,
+    },
+    /// Only detected if the new field lists the old name as an alias
+    RenameField {
+        old: String,
+        new: String,
+    },
+    ChangeType {
+        field: String,
+        old: ValueType,
+        new: ValueType,
+    },
+    SetNullable {
+        field: String,
+        nullable: bool,
+    },
+    SetDefault {
+        field: String,
+        default: Option<Value>,
+    },
+}
+
+impl std::fmt::Display for SchemaOp {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter) -> Result<(), std::fmt::Error> {
+        match self {
+            SchemaOp::ChangeKeyType { old, new } => {
+                write!(fmt, "Change key type from {:?} to {:?}", old, new)
+            }
+            SchemaOp::ChangeKeyFields { old, new } => {
+                write!(fmt, "Change key fields from {:?} to {:?}", old, new)
+            }
+            SchemaOp::AddField { name, vtype, .. } => {
+                write!(fmt, "Add field {} of type {:?}", name, vtype)
+            }
+            SchemaOp::DropField { name } => {
+                write!(fmt, "Drop field {}", name)
+            }
+            SchemaOp::RenameField { old, new } => {
+                write!(fmt, "Rename field {} to {}", old, new)
+            }
+            SchemaOp::ChangeType { field, old, new } => {
+                write!(
+                    fmt,
+                    "Change type of field {} from {:?} to {:?}",
+                    field, old, new
+                )
+            }
+            SchemaOp::SetNullable { field, nullable } => {
+                if *nullable {
+                    write!(fmt, "Make field {} nullable", field)
+                } else {
+                    write!(fmt, "Make field {} non-nullable", field)
+                }
+            }
+            SchemaOp::SetDefault { field, default } => match default {
+                Some(default) => write!(fmt, "Set default of field {} to {:?}", field, default),
+                None => write!(fmt, "Remove default of field {}", field),
+            },
+        }
+    }
+}
+
+impl Schema {
+    /// Lists the operations that turn this schema into `other`
+    ///
+    /// Dropped fields are listed first, followed by changes to the
+    /// remaining fields and added fields, in the order of `other`.
+    /// Renames are detected using the aliases of `other`.
+    pub fn diff(&self, other: &Schema) -> Vec<SchemaOp> {
+        let mut ops = Vec::new();
+
+        if self.key_fields != other.key_fields {
+            ops.push(SchemaOp::ChangeKeyFields {
+                old: self.key_fields.clone(),
+                new: other.key_fields.clone(),
+            });
+        } else if self.key != other.key {
+            ops.push(SchemaOp::ChangeKeyType {
+                old: self.key.clone(),
+                new: other.key.clone(),
+            });
+        }
+
+        let sources: Vec<_> = (0..other.fields.len())
+            .map(|pos| find_source_field(other, pos, self))
+            .collect();
+
+        for (pos, (fname, _)) in self.fields.iter().enumerate() {
+            if !sources.contains(&Some(pos)) {
+                ops.push(SchemaOp::DropField {
+                    name: fname.clone(),
+                });
+            }
+        }
+
+        for (pos, (fname, ftype)) in other.fields.iter().enumerate() {
+            let options = other.field_options(pos);
+
+            let Some(old_pos) = sources[pos] else {
+                ops.push(SchemaOp::AddField {
+                    name: fname.clone(),
+                    vtype: ftype.clone(),
+                    options: options.clone(),
+                });
+                continue;
+            };
+
+            let (old_name, old_type) = &self.fields[old_pos];
+            let old_options = self.field_options(old_pos);
+
+            if old_name != fname {
+                ops.push(SchemaOp::RenameField {
+                    old: old_name.clone(),
+                    new: fname.clone(),
+                });
+            }
+
+            if old_type != ftype {
+                ops.push(SchemaOp::ChangeType {
+                    field: fname.clone(),
+                    old: old_type.clone(),
+                    new: ftype.clone(),
+                });
+            }
+
+            if old_options.nullable != options.nullable {
+                ops.push(SchemaOp::SetNullable {
+                    field: fname.clone(),
+                    nullable: options.nullable,
+                });
+            }
+
+            if old_options.default != options.default {
+                ops.push(SchemaOp::SetDefault {
+                    field: fname.clone(),
+                    default: options.default.clone(),
+                });
+            }
+        }
+
+        ops
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SchemaOp;
+    use crate::{FieldOptions, SchemaBuilder, ValueType};
+
+    #[test]
+    fn diff() {
+        let old = SchemaBuilder::new(ValueType::U64)
+            .add_field("count", ValueType::I64)
+            .add_field("label", ValueType::String)
+            .add_field("flag", ValueType::Bool)
+            .build();
+
+        let new = SchemaBuilder::new(ValueType::I64)
+            .add_field("count", ValueType::F64)
+            .add_nullable_field("name", ValueType::String)
+            .add_alias("name", "label")
+            .add_field_with_default("extra", ValueType::U64, 1u64)
+            .build();
+
+        assert!(old.diff(&old).is_empty());
+
+        let ops = old.diff(&new);
+        assert_eq!(
+            ops,
+            vec![
+                SchemaOp::ChangeKeyType {
+                    old: ValueType::U64,
+                    new: ValueType::I64,
+                },
+                SchemaOp::DropField {
+                    name: "flag".to_string(),
+                },
+                SchemaOp::ChangeType {
+                    field: "count".to_string(),
+                    old: ValueType::I64,
+                    new: ValueType::F64,
+                },
+                SchemaOp::RenameField {
+                    old: "label".to_string(),
+                    new: "name".to_string(),
+                },
+                SchemaOp::SetNullable {
+                    field: "name".to_string(),
+                    nullable: true,
+                },
+                SchemaOp::AddField {
+                    name: "extra".to_string(),
+                    vtype: ValueType::U64,
+                    options: FieldOptions {
+                        default: Some(1u64.into()),
+                        ..Default::default()
+                    },
+                },
+            ]
+        );
+
+        assert_eq!(ops[3].to_string(), "Rename field label to name");
+    }
+}
@@ -0,0 +1,313 @@
+use serde::de::{Error as DeError, SeqAccess, Visitor};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::SchemaError;
+
+const HEADER_SIZE: usize = 8;
+const OFFSET_SIZE: usize = 4;
+
+/// A row of a table, stored in a single contiguous buffer
+///
+/// The buffer starts with a header consisting of the schema version and
+/// the number of fields (both as little-endian u32), followed by a bitmap
+/// that has one bit set for every null field, and a table of field offsets.
+/// The offset table has one more entry than there are fields, so that field
+/// `i` spans from offset `i` to offset `i+1`, relative to the end of the table.
+/// This gives constant-time access to every field.
+///
+/// Fields of fixed-size types, such as numbers, always occupy a slot of their
+/// type's width (see `ValueType::fixed_size`), even if they are null, so that
+/// they can be overwritten without moving the fields after them.
+/// Other null fields take no space.
+///
+/// Because the entry is a single buffer, it can be persisted and
+/// restored as-is using `as_bytes` and `from_bytes`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DataEntry {
+    data: Vec<u8>,
+}
+
+impl Default for DataEntry {
+    fn default() -> Self {
+        // Version zero, no fields and a single zero offset
+        Self {
+            data: vec![0u8; HEADER_SIZE + OFFSET_SIZE],
+        }
+    }
+}
+
+fn bitmap_size(num_fields: usize) -> usize {
+    num_fields.div_ceil(8)
+}
+
+/// Offsets are stored as u32, which limits the fields of an entry to 4 GiB
+fn encode_offset(offset: usize) -> Result<[u8; OFFSET_SIZE], SchemaError> {
+    match u32::try_from(offset) {
+        Ok(offset) => Ok(offset.to_le_bytes()),
+        Err(_) => Err(SchemaError::EntryTooLarge),
+    }
+}
+
+impl DataEntry {
+    /// Construct the DataEntry directly from its raw fields
+    /// It's the caller's responsibility to ensure these match the schema
+    pub fn try_from_fields(fields: Vec<Vec<u8>>) -> Result<Self, SchemaError> {
+        Self::from_parts(0, fields.into_iter().map(|f| (f, false)).collect())
+    }
+
+    /// Same as `try_from_fields`, but panics if the fields take up more than 4 GiB
+    pub fn from_fields(fields: Vec<Vec<u8>>) -> Self {
+        match Self::try_from_fields(fields) {
+            Ok(entry) => entry,
+            Err(err) => panic!("{}", err),
+        }
+    }
+
+    /// Creates an entry from the encoded value of each field and whether it is null
+    /// The bytes of null fields are stored as well, so that they can keep their slot.
+    pub(crate) fn from_parts(
+        version: u32,
+        fields: Vec<(Vec<u8>, bool)>,
+    ) -> Result<Self, SchemaError> {
+        let num_fields = u32::try_from(fields.len()).map_err(|_| SchemaError::EntryTooLarge)?;
+        let data_len: usize = fields.iter().map(|(f, _)| f.len()).sum();
+        let header_len = HEADER_SIZE + bitmap_size(fields.len()) + (fields.len() + 1) * OFFSET_SIZE;
+
+        let mut data = Vec::with_capacity(header_len + data_len);
+        data.extend_from_slice(&version.to_le_bytes());
+        data.extend_from_slice(&num_fields.to_le_bytes());
+
+        let mut bitmap = vec![0u8; bitmap_size(fields.len())];
+        for (pos, (_, is_null)) in fields.iter().enumerate() {
+            if *is_null {
+                bitmap[pos / 8] |= 1 << (pos % 8);
+            }
+        }
+        data.extend_from_slice(&bitmap);
+
+        let mut offset = 0usize;
+        data.extend_from_slice(&0u32.to_le_bytes());
+        for (field, _) in fields.iter() {
+            offset += field.len();
+            data.extend_from_slice(&encode_offset(offset)?);
+        }
+
+        for (field, _) in fields.into_iter() {
+            data.extend_from_slice(&field);
+        }
+
+        Ok(Self { data })
+    }
+
+    /// Restores an entry from the buffer returned by `as_bytes`
+    pub fn from_bytes(data: Vec<u8>) -> Result<Self, SchemaError> {
+        if data.len() < HEADER_SIZE {
+            return Err(SchemaError::EncodingError);
+        }
+
+        let entry = Self { data };
+        let num_fields = entry.num_fields();
+
+        if entry.data.len() < entry.data_start() {
+            return Err(SchemaError::EncodingError);
+        }
+
+        let mut last = 0;
+        for pos in 0..=num_fields {
+            let offset = entry.offset(pos);
+            if offset < last {
+                return Err(SchemaError::EncodingError);
+            }
+            last = offset;
+        }
+
+        if entry.data_start() + last != entry.data.len() {
+            return Err(SchemaError::EncodingError);
+        }
+
+        Ok(entry)
+    }
+
+    /// The underlying buffer, including the header
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.data
+    }
+
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.data
+    }
+
+    /// Marks the entry as written with the given schema version
+    #[must_use]
+    pub fn with_version(mut self, version: u32) -> Self {
+        self.data[..4].copy_from_slice(&version.to_le_bytes());
+        self
+    }
+
+    pub fn get_version(&self) -> u32 {
+        u32::from_le_bytes(self.data[..4].try_into().unwrap())
+    }
+
+    pub fn num_fields(&self) -> usize {
+        u32::from_le_bytes(self.data[4..HEADER_SIZE].try_into().unwrap()) as usize
+    }
+
+    fn offsets_start(&self) -> usize {
+        HEADER_SIZE + bitmap_size(self.num_fields())
+    }
+
+    fn data_start(&self) -> usize {
+        self.offsets_start() + (self.num_fields() + 1) * OFFSET_SIZE
+    }
+
+    fn offset(&self, pos: usize) -> usize {
+        let start = self.offsets_start() + pos * OFFSET_SIZE;
+        u32::from_le_bytes(self.data[start..start + OFFSET_SIZE].try_into().unwrap()) as usize
+    }
+
+    fn set_offset(&mut self, pos: usize, offset: [u8; OFFSET_SIZE]) {
+        let start = self.offsets_start() + pos * OFFSET_SIZE;
+        self.data[start..start + OFFSET_SIZE].copy_from_slice(&offset);
+    }
+
+    /// The encoded value of the field at `pos`, which is empty if the field is null
+    pub fn get_field_bytes(&self, pos: usize) -> &[u8] {
+        let data_start = self.data_start();
+        &self.data[data_start + self.offset(pos)..data_start + self.offset(pos + 1)]
+    }
+
+    /// Replaces the encoded value of the field at `pos`
+    pub(crate) fn set_field_bytes(&mut self, pos: usize, bytes: &[u8]) -> Result<(), SchemaError> {
+        let data_start = self.data_start();
+        let start = self.offset(pos);
+        let end = self.offset(pos + 1);
+
+        if bytes.len() != end - start {
+            // Check that all offsets still fit before modifying the entry
+            let num_fields = self.num_fields();
+            encode_offset(self.offset(num_fields) - (end - start) + bytes.len())?;
+
+            for idx in pos + 1..=num_fields {
+                let offset = self.offset(idx) - (end - start) + bytes.len();
+                self.set_offset(idx, encode_offset(offset)?);
+            }
+        }
+
+        self.data
+            .splice(data_start + start..data_start + end, bytes.iter().copied());
+
+        Ok(())
+    }
+
+    pub fn is_null(&self, pos: usize) -> bool {
+        self.data[HEADER_SIZE + pos / 8] & (1 << (pos % 8)) != 0
+    }
+
+    /// Sets the null bit of the field at `pos`, without changing its bytes
+    pub(crate) fn set_null(&mut self, pos: usize, is_null: bool) {
+        let idx = HEADER_SIZE + pos / 8;
+        let mask = 1 << (pos % 8);
+
+        if is_null {
+            self.data[idx] |= mask;
+        } else {
+            self.data[idx] &= !mask;
+        }
+    }
+}
+
+impl Serialize for DataEntry {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_bytes(&self.data)
+    }
+}
+
+struct EntryVisitor;
+
+impl<'de> Visitor<'de> for EntryVisitor {
+    type Value = DataEntry;
+
+    fn expecting(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(fmt, "an encoded DataEntry")
+    }
+
+    fn visit_bytes<E: DeError>(self, data: &[u8]) -> Result<DataEntry, E> {
+        self.visit_byte_buf(data.to_vec())
+    }
+
+    fn visit_byte_buf<E: DeError>(self, data: Vec<u8>) -> Result<DataEntry, E> {
+        DataEntry::from_bytes(data).map_err(E::custom)
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<DataEntry, A::Error> {
+        let mut data = Vec::with_capacity(seq.size_hint().unwrap_or(0));
+        while let Some(byte) = seq.next_element()? {
+            data.push(byte);
+        }
+
+        self.visit_byte_buf(data)
+    }
+}
+
+impl<'de> Deserialize<'de> for DataEntry {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_byte_buf(EntryVisitor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::DataEntry;
+
+    #[test]
+    fn modify_fields() {
+        let mut entry = DataEntry::from_parts(
+            3,
+            vec![(vec![1, 2], false), (vec![], true), (vec![3], false)],
+        )
+        .unwrap();
+
+        assert_eq!(entry.get_version(), 3);
+        assert_eq!(entry.num_fields(), 3);
+        assert!(entry.is_null(1));
+        assert_eq!(entry.get_field_bytes(2), &[3]);
+
+        entry.set_field_bytes(1, &[4, 5, 6]).unwrap();
+        entry.set_null(1, false);
+        entry.set_field_bytes(0, &[]).unwrap();
+
+        assert_eq!(
+            entry,
+            DataEntry::from_parts(
+                3,
+                vec![(vec![], false), (vec![4, 5, 6], false), (vec![3], false)]
+            )
+            .unwrap()
+        );
+
+        // Null fields keep their bytes
+        entry.set_null(1, true);
+        assert_eq!(
+            entry,
+            DataEntry::from_parts(
+                3,
+                vec![(vec![], false), (vec![4, 5, 6], true), (vec![3], false)]
+            )
+            .unwrap()
+        );
+    }
+
+    #[test]
+    fn serialize() {
+        let entry = DataEntry::from_fields(vec![vec![1, 2], vec![], vec![3; 20]]);
+
+        let data = bincode::serialize(&entry).unwrap();
+        assert_eq!(data.len(), entry.as_bytes().len() + 8);
+        assert_eq!(bincode::deserialize::<DataEntry>(&data).unwrap(), entry);
+
+        let restored = DataEntry::from_bytes(entry.as_bytes().to_vec()).unwrap();
+        assert_eq!(restored, entry);
+
+        assert!(DataEntry::from_bytes(entry.as_bytes()[..10].to_vec()).is_err());
+    }
+}
@@ -0,0 +1,740 @@
+//! Order-preserving encoding of keys
+//!
+//! Encoded keys compare (byte-wise) in the same order as the values they
+//! were created from, which makes them suitable for sorted key-value stores.
+//! Integers are stored big-endian with the sign bit flipped, floats use
+//! their total ordering, and strings are escaped and terminated, so that
+//! the encoding of a value is never a prefix of another.
+
+use crate::datetime::{Date, Duration, Time, Timestamp};
+use crate::decimal::Decimal;
+use crate::uuid::Uuid;
+use crate::{Schema, SchemaError, Value, ValueType};
+
+const KEY_NAME: &str = "key";
+
+const ESCAPE: u8 = 0x00;
+const ESCAPED_ZERO: u8 = 0xFF;
+const TERMINATOR: u8 = 0x00;
+
+fn encode_f64(f: f64) -> [u8; 8] {
+    let bits = f.to_bits();
+    let bits = if bits >> 63 == 1 {
+        !bits
+    } else {
+        bits ^ (1 << 63)
+    };
+
+    bits.to_be_bytes()
+}
+
+fn decode_f64(bytes: [u8; 8]) -> f64 {
+    let bits = u64::from_be_bytes(bytes);
+    let bits = if bits >> 63 == 1 {
+        bits ^ (1 << 63)
+    } else {
+        !bits
+    };
+
+    f64::from_bits(bits)
+}
+
+fn encode_f32(f: f32) -> [u8; 4] {
+    let bits = f.to_bits();
+    let bits = if bits >> 31 == 1 {
+        !bits
+    } else {
+        bits ^ (1 << 31)
+    };
+
+    bits.to_be_bytes()
+}
+
+fn decode_f32(bytes: [u8; 4]) -> f32 {
+    let bits = u32::from_be_bytes(bytes);
+    let bits = if bits >> 31 == 1 {
+        bits ^ (1 << 31)
+    } else {
+        !bits
+    };
+
+    f32::from_bits(bits)
+}
+
+fn encode_i64(i: i64) -> [u8; 8] {
+    ((i as u64) ^ (1 << 63)).to_be_bytes()
+}
+
+fn decode_i64(bytes: [u8; 8]) -> i64 {
+    (u64::from_be_bytes(bytes) ^ (1 << 63)) as i64
+}
+
+fn encode_bytes(data: &[u8], buf: &mut Vec<u8>) {
+    for byte in data {
+        buf.push(*byte);
+        if *byte == ESCAPE {
+            buf.push(ESCAPED_ZERO);
+        }
+    }
+
+    buf.push(ESCAPE);
+    buf.push(TERMINATOR);
+}
+
+fn decode_bytes(data: &mut &[u8]) -> Result<Vec<u8>, SchemaError> {
+    let mut result = Vec::new();
+
+    loop {
+        let Some((byte, rest)) = data.split_first() else {
+            return Err(SchemaError::EncodingError);
+        };
+        *data = rest;
+
+        if *byte != ESCAPE {
+            result.push(*byte);
+            continue;
+        }
+
+        let Some((next, rest)) = data.split_first() else {
+            return Err(SchemaError::EncodingError);
+        };
+        *data = rest;
+
+        match *next {
+            TERMINATOR => return Ok(result),
+            ESCAPED_ZERO => result.push(ESCAPE),
+            _ => return Err(SchemaError::EncodingError),
+        }
+    }
+}
+
+fn take<const N: usize>(data: &mut &[u8]) -> Result<[u8; N], SchemaError> {
+    if data.len() < N {
+        return Err(SchemaError::EncodingError);
+    }
+
+    let (head, rest) = data.split_at(N);
+    *data = rest;
+    Ok(head.try_into().unwrap())
+}
+
+/// Encodes a 64-bit number into a narrow key component
+/// Returns false if the value does not fit.
+fn encode_narrow(value: &Value, vtype: &ValueType, buf: &mut Vec<u8>) -> bool {
+    match (value, vtype) {
+        (Value::I64(i), ValueType::I8) => match i8::try_from(*i) {
+            Ok(i) => buf.push((i as u8) ^ (1 << 7)),
+            Err(_) => return false,
+        },
+        (Value::I64(i), ValueType::I16) => match i16::try_from(*i) {
+            Ok(i) => buf.extend_from_slice(&((i as u16) ^ (1 << 15)).to_be_bytes()),
+            Err(_) => return false,
+        },
+        (Value::I64(i), ValueType::I32) => match i32::try_from(*i) {
+            Ok(i) => buf.extend_from_slice(&((i as u32) ^ (1 << 31)).to_be_bytes()),
+            Err(_) => return false,
+        },
+        (Value::U64(u), ValueType::U8) => match u8::try_from(*u) {
+            Ok(u) => buf.push(u),
+            Err(_) => return false,
+        },
+        (Value::U64(u), ValueType::U16) => match u16::try_from(*u) {
+            Ok(u) => buf.extend_from_slice(&u.to_be_bytes()),
+            Err(_) => return false,
+        },
+        (Value::U64(u), ValueType::U32) => match u32::try_from(*u) {
+            Ok(u) => buf.extend_from_slice(&u.to_be_bytes()),
+            Err(_) => return false,
+        },
+        (Value::F64(f), ValueType::F32) => {
+            let narrow = *f as f32;
+            if narrow.is_infinite() && f.is_finite() {
+                return false;
+            }
+            buf.extend_from_slice(&encode_f32(narrow));
+        }
+        _ => return false,
+    }
+
+    true
+}
+
+/// Whether values of this type have an order-preserving encoding
+pub(crate) fn is_key_type(vtype: &ValueType) -> bool {
+    match vtype {
+        ValueType::List(_) | ValueType::Map(..) | ValueType::Struct(_) => false,
+        #[cfg(feature = "json")]
+        ValueType::Json => false,
+        _ => true,
+    }
+}
+
+/// Appends the encoding of `value` to `buf`
+pub fn encode_key_into(
+    value: &Value,
+    vtype: &ValueType,
+    buf: &mut Vec<u8>,
+) -> Result<(), SchemaError> {
+    encode_component(KEY_NAME, value, vtype, buf)
+}
+
+fn encode_component(
+    name: &str,
+    value: &Value,
+    vtype: &ValueType,
+    buf: &mut Vec<u8>,
+) -> Result<(), SchemaError> {
+    match (value, vtype) {
+        (Value::String(s), ValueType::String) => encode_bytes(s.as_bytes(), buf),
+        // Enum keys sort by the position of their label
+        (Value::String(s), ValueType::Enum(labels)) => {
+            let Some(idx) = labels.iter().position(|label| label == s) else {
+                return Err(SchemaError::UnknownLabel {
+                    field: name.to_string(),
+                    label: s.clone(),
+                });
+            };
+            buf.extend_from_slice(&(idx as u32).to_be_bytes())
+        }
+        (Value::Bytes(b), ValueType::Bytes) => encode_bytes(b, buf),
+        (Value::F64(f), ValueType::F64) => buf.extend_from_slice(&encode_f64(*f)),
+        (Value::I64(i), ValueType::I64) => buf.extend_from_slice(&encode_i64(*i)),
+        (Value::U64(u), ValueType::U64) => buf.extend_from_slice(&u.to_be_bytes()),
+        (Value::I128(i), ValueType::I128) => {
+            buf.extend_from_slice(&((*i as u128) ^ (1 << 127)).to_be_bytes())
+        }
+        (Value::U128(u), ValueType::U128) => buf.extend_from_slice(&u.to_be_bytes()),
+        // Widened the same way as field values
+        (Value::I64(i), ValueType::I128) => {
+            encode_component(name, &Value::I128(*i as i128), vtype, buf)?
+        }
+        (Value::U64(u), ValueType::I128) => {
+            encode_component(name, &Value::I128(*u as i128), vtype, buf)?
+        }
+        (Value::U64(u), ValueType::U128) => {
+            encode_component(name, &Value::U128(*u as u128), vtype, buf)?
+        }
+        (Value::I64(i), ValueType::U128) => match u128::try_from(*i) {
+            Ok(u) => buf.extend_from_slice(&u.to_be_bytes()),
+            Err(_) => return Err(SchemaError::OutOfRange(name.to_string())),
+        },
+        (
+            Value::I64(_) | Value::U64(_) | Value::F64(_),
+            ValueType::I8
+            | ValueType::I16
+            | ValueType::I32
+            | ValueType::U8
+            | ValueType::U16
+            | ValueType::U32
+            | ValueType::F32,
+        ) if value.get_type() == Some(vtype.widened()) => {
+            if !encode_narrow(value, vtype, buf) {
+                return Err(SchemaError::OutOfRange(name.to_string()));
+            }
+        }
+        (Value::Bool(b), ValueType::Bool) => buf.push(*b as u8),
+        (Value::Timestamp(ts), ValueType::Timestamp { unit, .. }) => {
+            let Some(ts) = ts.to_unit(*unit) else {
+                return Err(SchemaError::OutOfRange(name.to_string()));
+            };
+            buf.extend_from_slice(&encode_i64(ts.value()))
+        }
+        (Value::Date(date), ValueType::Date) => {
+            buf.extend_from_slice(&((date.days() as u32) ^ (1 << 31)).to_be_bytes())
+        }
+        (Value::Time(time), ValueType::Time) => buf.extend_from_slice(&encode_i64(time.nanos())),
+        (Value::Duration(duration), ValueType::Duration) => {
+            buf.extend_from_slice(&encode_i64(duration.nanos()))
+        }
+        (Value::Decimal(decimal), ValueType::Decimal { precision, scale }) => {
+            let Some(decimal) = decimal.fit_to(*precision, *scale) else {
+                return Err(SchemaError::OutOfRange(name.to_string()));
+            };
+            let mantissa = (decimal.mantissa() as u128) ^ (1 << 127);
+            buf.extend_from_slice(&mantissa.to_be_bytes())
+        }
+        (Value::Uuid(uuid), ValueType::Uuid) => buf.extend_from_slice(uuid.as_bytes()),
+        (Value::Null, _) => return Err(SchemaError::NotNullable(name.to_string())),
+        (value, vtype) => {
+            let got = value.get_type().unwrap();
+
+            return if &got == vtype {
+                Err(SchemaError::UnsupportedKeyType(vtype.clone()))
+            } else {
+                Err(SchemaError::TypeMismatch {
+                    field: name.to_string(),
+                    expected: vtype.clone(),
+                    got,
+                })
+            };
+        }
+    }
+
+    Ok(())
+}
+
+/// Reads a single key of type `vtype` from the start of `data`
+/// and advances `data` past it
+pub fn decode_key_from(data: &mut &[u8], vtype: &ValueType) -> Result<Value, SchemaError> {
+    let value = match vtype {
+        ValueType::String => {
+            let bytes = decode_bytes(data)?;
+            let s = String::from_utf8(bytes).map_err(|_| SchemaError::EncodingError)?;
+            Value::String(s)
+        }
+        ValueType::Bytes => Value::Bytes(decode_bytes(data)?),
+        ValueType::Enum(labels) => {
+            let idx = u32::from_be_bytes(take(data)?) as usize;
+            let label = labels.get(idx).ok_or(SchemaError::EncodingError)?;
+            Value::String(label.clone())
+        }
+        ValueType::F64 => Value::F64(decode_f64(take(data)?)),
+        ValueType::I64 => Value::I64(decode_i64(take(data)?)),
+        ValueType::U64 => Value::U64(u64::from_be_bytes(take(data)?)),
+        ValueType::I128 => Value::I128((u128::from_be_bytes(take(data)?) ^ (1 << 127)) as i128),
+        ValueType::U128 => Value::U128(u128::from_be_bytes(take(data)?)),
+        ValueType::I8 => Value::I64((take::<1>(data)?[0] ^ (1 << 7)) as i8 as i64),
+        ValueType::I16 => Value::I64((u16::from_be_bytes(take(data)?) ^ (1 << 15)) as i16 as i64),
+        ValueType::I32 => Value::I64((u32::from_be_bytes(take(data)?) ^ (1 << 31)) as i32 as i64),
+        ValueType::U8 => Value::U64(take::<1>(data)?[0] as u64),
+        ValueType::U16 => Value::U64(u16::from_be_bytes(take(data)?) as u64),
+        ValueType::U32 => Value::U64(u32::from_be_bytes(take(data)?) as u64),
+        ValueType::F32 => Value::F64(decode_f32(take(data)?) as f64),
+        ValueType::Bool => match take::<1>(data)? {
+            [0] => Value::Bool(false),
+            [1] => Value::Bool(true),
+            _ => return Err(SchemaError::EncodingError),
+        },
+        ValueType::Timestamp { unit, .. } => {
+            Value::Timestamp(Timestamp::new(decode_i64(take(data)?), *unit))
+        }
+        ValueType::Date => {
+            let days = (u32::from_be_bytes(take(data)?) ^ (1 << 31)) as i32;
+            Value::Date(Date::from_days(days))
+        }
+        ValueType::Time => {
+            let time = Time::from_nanos(decode_i64(take(data)?));
+            Value::Time(time.ok_or(SchemaError::EncodingError)?)
+        }
+        ValueType::Duration => Value::Duration(Duration::from_nanos(decode_i64(take(data)?))),
+        ValueType::Decimal { scale, .. } => {
+            let mantissa = (u128::from_be_bytes(take(data)?) ^ (1 << 127)) as i128;
+            Value::Decimal(Decimal::new(mantissa, *scale).ok_or(SchemaError::EncodingError)?)
+        }
+        ValueType::Uuid => Value::Uuid(Uuid::from_bytes(take(data)?)),
+        ValueType::List(_) | ValueType::Map(..) | ValueType::Struct(_) => {
+            return Err(SchemaError::UnsupportedKeyType(vtype.clone()))
+        }
+        #[cfg(feature = "json")]
+        ValueType::Json => return Err(SchemaError::UnsupportedKeyType(vtype.clone())),
+    };
+
+    Ok(value)
+}
+
+pub fn encode_key(value: &Value, vtype: &ValueType) -> Result<Vec<u8>, SchemaError> {
+    let mut buf = Vec::new();
+    encode_key_into(value, vtype, &mut buf)?;
+    Ok(buf)
+}
+
+/// Decodes a key created by `encode_key`
+pub fn decode_key(mut data: &[u8], vtype: &ValueType) -> Result<Value, SchemaError> {
+    let value = decode_key_from(&mut data, vtype)?;
+
+    if data.is_empty() {
+        Ok(value)
+    } else {
+        Err(SchemaError::EncodingError)
+    }
+}
+
+/// The smallest key that is greater than all keys starting with `prefix`
+///
+/// Together with the prefix itself, this is the range to scan for all
+/// keys that start with it. Returns None if there is no upper bound.
+pub fn prefix_upper_bound(prefix: &[u8]) -> Option<Vec<u8>> {
+    let mut bound = prefix.to_vec();
+
+    while let Some(last) = bound.pop() {
+        if last < u8::MAX {
+            bound.push(last + 1);
+            return Some(bound);
+        }
+    }
+
+    None
+}
+
+impl Schema {
+    /// Encodes a key of this schema so that it sorts correctly
+    /// Encodes a single-valued key
+    /// Composite keys are rejected; use `encode_composite_key` for those.
+    pub fn encode_key(&self, value: &Value) -> Result<Vec<u8>, SchemaError> {
+        if self.is_composite_key() {
+            return Err(SchemaError::CompositeKey);
+        }
+
+        encode_key(value, &self.key)
+    }
+
+    /// Decodes a single-valued key
+    /// Composite keys are rejected; use `decode_composite_key` for those.
+    pub fn decode_key(&self, data: &[u8]) -> Result<Value, SchemaError> {
+        if self.is_composite_key() {
+            return Err(SchemaError::CompositeKey);
+        }
+
+        decode_key(data, &self.key)
+    }
+
+    /// Encodes all components of a key, which sorts by its components in order
+    /// Schemas without a composite key are treated as having a single component.
+    pub fn encode_composite_key(&self, values: &[Value]) -> Result<Vec<u8>, SchemaError> {
+        if values.len() != self.key_component_count() {
+            return Err(SchemaError::EncodingError);
+        }
+
+        self.encode_key_prefix(values)
+    }
+
+    /// Encodes the first `values.len()` components of a key
+    /// All keys starting with these components start with the result.
+    pub fn encode_key_prefix(&self, values: &[Value]) -> Result<Vec<u8>, SchemaError> {
+        if values.len() > self.key_component_count() {
+            return Err(SchemaError::EncodingError);
+        }
+
+        let mut buf = Vec::new();
+
+        for (pos, value) in values.iter().enumerate() {
+            let (name, vtype) = self.key_component(pos);
+            encode_component(name, value, vtype, &mut buf)?;
+        }
+
+        Ok(buf)
+    }
+
+    /// The range of encoded keys that start with the given components
+    /// The start is inclusive and the end exclusive, or None if unbounded.
+    pub fn key_prefix_range(
+        &self,
+        values: &[Value],
+    ) -> Result<(Vec<u8>, Option<Vec<u8>>), SchemaError> {
+        let start = self.encode_key_prefix(values)?;
+        let end = prefix_upper_bound(&start);
+        Ok((start, end))
+    }
+
+    pub fn decode_composite_key(&self, mut data: &[u8]) -> Result<Vec<Value>, SchemaError> {
+        let mut result = Vec::new();
+
+        for pos in 0..self.key_component_count() {
+            let (_, vtype) = self.key_component(pos);
+            result.push(decode_key_from(&mut data, vtype)?);
+        }
+
+        if data.is_empty() {
+            Ok(result)
+        } else {
+            Err(SchemaError::EncodingError)
+        }
+    }
+
+    fn key_component_count(&self) -> usize {
+        self.key_fields.len().max(1)
+    }
+
+    fn key_component(&self, pos: usize) -> (&str, &ValueType) {
+        match self.key_fields.get(pos) {
+            Some((name, vtype)) => (name, vtype),
+            None => (KEY_NAME, &self.key),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{decode_key, encode_key};
+    use crate::{Date, SchemaBuilder, SchemaError, TimeUnit, Timestamp, Uuid, Value, ValueType};
+
+    fn check_order(values: Vec<Value>, vtype: ValueType) {
+        let encoded: Vec<_> = values
+            .iter()
+            .map(|v| encode_key(v, &vtype).unwrap())
+            .collect();
+
+        for (pos, data) in encoded.iter().enumerate() {
+            assert_eq!(decode_key(data, &vtype).unwrap(), values[pos]);
+
+            if pos > 0 {
+                assert!(encoded[pos - 1] < *data, "{:?}", values[pos]);
+            }
+        }
+    }
+
+    #[test]
+    fn integer_order() {
+        check_order(
+            vec![i64::MIN, -256, -1, 0, 1, 255, i64::MAX]
+                .into_iter()
+                .map(Value::from)
+                .collect(),
+            ValueType::I64,
+        );
+        check_order(
+            vec![0u64, 1, 256, u64::MAX]
+                .into_iter()
+                .map(Value::from)
+                .collect(),
+            ValueType::U64,
+        );
+    }
+
+    #[test]
+    fn wide_integer_order() {
+        check_order(
+            vec![i128::MIN, -(1 << 64), -1, 0, 1 << 64, i128::MAX]
+                .into_iter()
+                .map(Value::from)
+                .collect(),
+            ValueType::I128,
+        );
+        check_order(
+            vec![0u128, 1, 1 << 64, u128::MAX]
+                .into_iter()
+                .map(Value::from)
+                .collect(),
+            ValueType::U128,
+        );
+    }
+
+    #[test]
+    fn narrow_order() {
+        check_order(
+            vec![i32::MIN as i64, -1, 0, 1, i32::MAX as i64]
+                .into_iter()
+                .map(Value::from)
+                .collect(),
+            ValueType::I32,
+        );
+        check_order(
+            vec![-1.5, -0.0, 0.0, 2.5]
+                .into_iter()
+                .map(Value::from)
+                .collect(),
+            ValueType::F32,
+        );
+
+        assert_eq!(
+            encode_key(&Value::I64(-1), &ValueType::I8).unwrap().len(),
+            1
+        );
+        assert_eq!(
+            encode_key(&Value::U64(256), &ValueType::U8),
+            Err(SchemaError::OutOfRange("key".to_string()))
+        );
+    }
+
+    #[test]
+    fn float_order() {
+        check_order(
+            vec![
+                f64::NEG_INFINITY,
+                -1e10,
+                -0.5,
+                -0.0,
+                0.0,
+                1e-10,
+                2.5,
+                f64::INFINITY,
+            ]
+            .into_iter()
+            .map(Value::from)
+            .collect(),
+            ValueType::F64,
+        );
+    }
+
+    #[test]
+    fn string_order() {
+        check_order(
+            vec!["", "\0", "\0\0", "a", "a\0", "a\0b", "ab", "b"]
+                .into_iter()
+                .map(Value::from)
+                .collect(),
+            ValueType::String,
+        );
+    }
+
+    #[test]
+    fn bytes_order() {
+        check_order(
+            vec![
+                vec![],
+                vec![0],
+                vec![0, 0],
+                vec![0, 255],
+                vec![1],
+                vec![255, 0],
+            ]
+            .into_iter()
+            .map(Value::from)
+            .collect(),
+            ValueType::Bytes,
+        );
+    }
+
+    #[test]
+    fn datetime_order() {
+        check_order(
+            vec![
+                "1900-01-01T00:00:00.001Z",
+                "1969-12-31T23:59:59.999Z",
+                "1970-01-01T00:00:00.000Z",
+                "2024-03-01T10:30:05.250Z",
+            ]
+            .into_iter()
+            .map(|s| Timestamp::parse(s).unwrap().into())
+            .collect(),
+            ValueType::Timestamp {
+                unit: TimeUnit::Milliseconds,
+                timezone: None,
+            },
+        );
+        check_order(
+            vec!["0001-01-01", "1969-12-31", "1970-01-01", "9999-12-31"]
+                .into_iter()
+                .map(|s| Date::parse(s).unwrap().into())
+                .collect(),
+            ValueType::Date,
+        );
+    }
+
+    #[test]
+    fn uuid_order() {
+        let uuids: Vec<Value> = [0, 1, 1 << 64, u128::MAX]
+            .into_iter()
+            .map(|u| Uuid::from_u128(u).into())
+            .collect();
+
+        for uuid in uuids.iter() {
+            assert_eq!(encode_key(uuid, &ValueType::Uuid).unwrap().len(), 16);
+        }
+
+        check_order(uuids, ValueType::Uuid);
+    }
+
+    #[test]
+    fn key_type_mismatch() {
+        assert_eq!(
+            encode_key(&42i64.into(), &ValueType::String),
+            Err(SchemaError::TypeMismatch {
+                field: "key".to_string(),
+                expected: ValueType::String,
+                got: ValueType::I64,
+            })
+        );
+    }
+
+    #[test]
+    fn composite_key() {
+        let schema = SchemaBuilder::with_composite_key(vec![
+            ("tenant", ValueType::String),
+            ("timestamp", ValueType::I64),
+            ("id", ValueType::U64),
+        ])
+        .build();
+
+        let keys: Vec<Vec<Value>> = vec![
+            vec!["a".into(), (-5i64).into(), 3u64.into()],
+            vec!["a".into(), 1i64.into(), 1u64.into()],
+            vec!["a".into(), 1i64.into(), 2u64.into()],
+            vec!["ab".into(), i64::MIN.into(), 0u64.into()],
+            vec!["b".into(), 0i64.into(), 0u64.into()],
+        ];
+
+        let encoded: Vec<_> = keys
+            .iter()
+            .map(|k| schema.encode_composite_key(k).unwrap())
+            .collect();
+
+        for (pos, data) in encoded.iter().enumerate() {
+            assert_eq!(schema.decode_composite_key(data).unwrap(), keys[pos]);
+
+            if pos > 0 {
+                assert!(encoded[pos - 1] < *data);
+            }
+        }
+
+        let (start, end) = schema.key_prefix_range(&["a".into()]).unwrap();
+        let end = end.unwrap();
+
+        let in_range: Vec<_> = encoded
+            .iter()
+            .filter(|k| **k >= start && **k < end)
+            .collect();
+        assert_eq!(in_range.len(), 3);
+
+        let (start, end) = schema.key_prefix_range(&["a".into(), 1i64.into()]).unwrap();
+        let end = end.unwrap();
+        assert!(encoded[0] < start);
+        assert!(encoded[1] >= start && encoded[2] < end);
+        assert!(encoded[3] >= end);
+
+        assert!(schema.encode_composite_key(&["a".into()]).is_err());
+
+        assert_eq!(
+            schema.encode_key(&"a".into()),
+            Err(SchemaError::CompositeKey)
+        );
+        assert_eq!(
+            schema.decode_key(&encoded[0]),
+            Err(SchemaError::CompositeKey)
+        );
+    }
+
+    #[test]
+    fn invalid_composite_key() {
+        let empty: Vec<(&str, ValueType)> = vec![];
+        assert_eq!(
+            SchemaBuilder::with_composite_key(empty).try_build().err(),
+            Some(SchemaError::EmptyKey)
+        );
+
+        let duplicate =
+            SchemaBuilder::with_composite_key(vec![("id", ValueType::U64), ("id", ValueType::I64)]);
+        assert_eq!(
+            duplicate.try_build().err(),
+            Some(SchemaError::DuplicateField("id".to_string()))
+        );
+
+        let list = ValueType::List(Box::new(ValueType::U64));
+        assert_eq!(
+            SchemaBuilder::new(ValueType::Json).try_build().err(),
+            Some(SchemaError::UnsupportedKeyType(ValueType::Json))
+        );
+        assert_eq!(
+            SchemaBuilder::with_composite_key(vec![("id", ValueType::U64), ("tags", list.clone())])
+                .try_build()
+                .err(),
+            Some(SchemaError::UnsupportedKeyType(list))
+        );
+    }
+
+    #[test]
+    fn widened_key() {
+        assert_eq!(
+            encode_key(&(-3i64).into(), &ValueType::I128),
+            encode_key(&Value::I128(-3), &ValueType::I128)
+        );
+        assert_eq!(
+            encode_key(&3u64.into(), &ValueType::U128),
+            encode_key(&Value::U128(3), &ValueType::U128)
+        );
+        assert_eq!(
+            encode_key(&(-3i64).into(), &ValueType::U128),
+            Err(SchemaError::OutOfRange("key".to_string()))
+        );
+    }
+}
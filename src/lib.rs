@@ -1,13 +1,61 @@
 use serde::{Deserialize, Serialize};
 
+use std::borrow::Cow;
 use std::collections::HashMap;
+use std::sync::Arc;
 
 mod value;
-pub use value::{Value, ValueType};
+pub use value::{HasValueType, Value, ValueType};
+
+pub mod datetime;
+pub use datetime::{Date, Duration, Time, TimeUnit, Timestamp};
+
+mod decimal;
+pub use decimal::{Decimal, RoundingMode};
+
+mod uuid;
+pub use uuid::Uuid;
+
+mod list;
+pub use list::{ListIter, ListRef};
+
+mod map;
+pub use map::{MapIter, MapRef};
+
+mod record;
+pub use record::StructRef;
+
+mod value_ref;
+pub use value_ref::ValueRef;
 
 mod builders;
 pub use builders::{EntryBuilder, SchemaBuilder};
 
+mod entry;
+pub use entry::DataEntry;
+
+mod migration;
+pub use migration::SchemaHistory;
+
+mod compat;
+pub use compat::{BreakingChange, Compatibility, CompatibilityLevel};
+
+mod diff;
+pub use diff::SchemaOp;
+
+pub mod key;
+
+mod serialization;
+
+mod projection;
+pub use projection::Projector;
+
+pub mod typed;
+pub use typed::Record;
+
+#[cfg(feature = "derive")]
+pub use schema_derive::Record;
+
 #[cfg(all(feature = "json", feature = "python-bindings"))]
 pub use value::{python_to_json, python_to_json_value};
 
@@ -17,6 +65,44 @@ pub type Tuple = Vec<(String, Value)>;
 pub enum SchemaError {
     NoSuchField(String),
     EncodingError,
+    TypeMismatch {
+        field: String,
+        expected: ValueType,
+        got: ValueType,
+    },
+    NotNullable(String),
+    GeneratedField(String),
+    VersionMismatch {
+        expected: u32,
+        got: u32,
+    },
+    UnknownVersion(u32),
+    UnsupportedKeyType(ValueType),
+    InvalidEntry(EntryError),
+    /// A value does not fit into its field, e.g., a timestamp that overflows the field's unit
+    OutOfRange(String),
+    ParseError(String),
+    InvalidTimezone(String),
+    /// A type that cannot be declared in a schema, e.g., a decimal with a scale larger than its precision,
+    /// or the type of a field or struct member whose name contains a `.`
+    InvalidType(ValueType),
+    /// A map or struct value contains the same key or member more than once
+    DuplicateKey(String),
+    /// A string written to an enum field is not one of its labels
+    UnknownLabel {
+        field: String,
+        label: String,
+    },
+    /// Converting a value to or from an entry using serde failed
+    Serde(String),
+    /// The fields of an entry take up more than 4 GiB
+    EntryTooLarge,
+    /// A field or key component is defined, or projected, more than once
+    DuplicateField(String),
+    /// A composite key was declared without any components
+    EmptyKey,
+    /// A single key value was used with a schema whose key has multiple components
+    CompositeKey,
 }
 
 impl std::fmt::Display for SchemaError {
@@ -28,40 +114,623 @@ impl std::fmt::Display for SchemaError {
             SchemaError::EncodingError => {
                 write!(fmt, "Failed to encode or decode data")
             }
+            SchemaError::TypeMismatch {
+                field,
+                expected,
+                got,
+            } => {
+                write!(
+                    fmt,
+                    "Type mismatch for field {}: expected {:?}, got {:?}",
+                    field, expected, got
+                )
+            }
+            SchemaError::NotNullable(fname) => {
+                write!(fmt, "Field is not nullable: {}", fname)
+            }
+            SchemaError::GeneratedField(fname) => {
+                write!(fmt, "Cannot write generated field: {}", fname)
+            }
+            SchemaError::VersionMismatch { expected, got } => {
+                write!(
+                    fmt,
+                    "Entry was written with schema version {}, expected {}",
+                    got, expected
+                )
+            }
+            SchemaError::UnknownVersion(version) => {
+                write!(fmt, "Unknown schema version: {}", version)
+            }
+            SchemaError::UnsupportedKeyType(vtype) => {
+                write!(fmt, "Type cannot be used as a key: {:?}", vtype)
+            }
+            SchemaError::InvalidEntry(err) => {
+                write!(fmt, "Invalid entry: {}", err)
+            }
+            SchemaError::OutOfRange(fname) => {
+                write!(fmt, "Value out of range for field: {}", fname)
+            }
+            SchemaError::ParseError(input) => {
+                write!(fmt, "Failed to parse: {}", input)
+            }
+            SchemaError::InvalidTimezone(tz) => {
+                write!(fmt, "Invalid timezone: {}", tz)
+            }
+            SchemaError::InvalidType(vtype) => {
+                write!(fmt, "Invalid type: {:?}", vtype)
+            }
+            SchemaError::DuplicateKey(fname) => {
+                write!(fmt, "Duplicate key or member in field: {}", fname)
+            }
+            SchemaError::UnknownLabel { field, label } => {
+                write!(fmt, "Unknown label for field {}: {}", field, label)
+            }
+            SchemaError::Serde(msg) => {
+                write!(fmt, "Serde error: {}", msg)
+            }
+            SchemaError::EntryTooLarge => {
+                write!(fmt, "Entry is too large")
+            }
+            SchemaError::DuplicateField(fname) => {
+                write!(fmt, "Field defined more than once: {}", fname)
+            }
+            SchemaError::EmptyKey => {
+                write!(fmt, "Composite key needs at least one component")
+            }
+            SchemaError::CompositeKey => {
+                write!(fmt, "Key has multiple components")
+            }
         }
     }
 }
 
 impl std::error::Error for SchemaError {}
 
-#[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
-pub struct DataEntry {
-    fields: Vec<Vec<u8>>,
+/// Everything that was wrong with an entry passed to `EntryBuilder::try_build`
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct EntryError {
+    /// Fields that are part of the schema but were never set
+    pub missing_fields: Vec<String>,
+    /// Fields that were set but are not part of the schema
+    pub unknown_fields: Vec<String>,
+    /// Values that could not be stored in their field, e.g. `SchemaError::TypeMismatch`
+    pub invalid_values: Vec<SchemaError>,
+}
+
+impl EntryError {
+    pub fn is_empty(&self) -> bool {
+        self.missing_fields.is_empty()
+            && self.unknown_fields.is_empty()
+            && self.invalid_values.is_empty()
+    }
+}
+
+impl std::fmt::Display for EntryError {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter) -> Result<(), std::fmt::Error> {
+        let mut parts = Vec::new();
+
+        if !self.missing_fields.is_empty() {
+            parts.push(format!(
+                "Missing fields: {}",
+                self.missing_fields.join(", ")
+            ));
+        }
+
+        if !self.unknown_fields.is_empty() {
+            parts.push(format!(
+                "Unknown fields: {}",
+                self.unknown_fields.join(", ")
+            ));
+        }
+
+        for err in self.invalid_values.iter() {
+            parts.push(err.to_string());
+        }
+
+        write!(fmt, "{}", parts.join("; "))
+    }
+}
+
+/// Reads the field at `pos`, which is `Value::Null` if its null bit is set
+fn read_field(entry: &DataEntry, pos: usize, ftype: &ValueType) -> Result<Value, SchemaError> {
+    if entry.is_null(pos) {
+        return Ok(Value::Null);
+    }
+
+    match Value::from_bytes(entry.get_field_bytes(pos), ftype) {
+        Ok(v) => Ok(v),
+        Err(_) => {
+            log::error!("Failed to deserialize field of type {:?}", ftype);
+            Err(SchemaError::EncodingError)
+        }
+    }
+}
+
+/// Same as read_field but borrows the value from the entry
+fn read_field_ref<'a>(
+    entry: &'a DataEntry,
+    pos: usize,
+    ftype: &'a ValueType,
+) -> Result<ValueRef<'a>, SchemaError> {
+    if entry.is_null(pos) {
+        Ok(ValueRef::Null)
+    } else {
+        ValueRef::from_bytes(entry.get_field_bytes(pos), ftype)
+    }
+}
+
+/// The bytes stored for a field of type `ftype` that is null
+/// Fixed-size fields keep a zeroed slot, so that setting them never moves other fields.
+fn null_slot(ftype: &ValueType) -> Vec<u8> {
+    vec![0; ftype.fixed_size().unwrap_or(0)]
+}
+
+/// Stores a value encoded by encode_value at `pos`
+fn write_field(
+    entry: &mut DataEntry,
+    pos: usize,
+    ftype: &ValueType,
+    bytes: Option<Vec<u8>>,
+) -> Result<(), SchemaError> {
+    let is_null = bytes.is_none();
+    let bytes = bytes.unwrap_or_else(|| null_slot(ftype));
+
+    entry.set_field_bytes(pos, &bytes)?;
+    entry.set_null(pos, is_null);
+    Ok(())
+}
+
+type GeneratorFn = dyn Fn(&HashMap<String, Value>) -> Value + Send + Sync;
+
+/// Computes the value of a generated field from the entry's other fields
+#[derive(Clone)]
+pub struct Generator(Arc<GeneratorFn>);
+
+impl Generator {
+    pub fn new<F>(func: F) -> Self
+    where
+        F: Fn(&HashMap<String, Value>) -> Value + Send + Sync + 'static,
+    {
+        Self(Arc::new(func))
+    }
+
+    pub fn generate(&self, fields: &HashMap<String, Value>) -> Value {
+        (self.0)(fields)
+    }
+}
+
+impl std::fmt::Debug for Generator {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter) -> Result<(), std::fmt::Error> {
+        write!(fmt, "Generator")
+    }
 }
 
-impl DataEntry {
-    /// Construct the DataEntry directly from its raw fields
-    /// It's the caller's responsibility to ensure these match the schema
-    pub fn from_fields(fields: Vec<Vec<u8>>) -> Self {
-        Self { fields }
+impl PartialEq for Generator {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
     }
 }
 
+/// Per-field metadata that is not part of the field's type
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FieldOptions {
+    /// Whether the field may be set to `Value::Null`
+    pub nullable: bool,
+    /// The value used by `EntryBuilder` if the field is not set
+    pub default: Option<Value>,
+    /// Computes the field when an entry is built
+    /// Generators cannot be serialized, so they must be re-attached
+    /// using `Schema::set_generator` after deserializing a schema.
+    #[serde(skip)]
+    pub generator: Option<Generator>,
+    /// Names this field had in previous versions of the schema
+    #[serde(default)]
+    pub aliases: Vec<String>,
+}
+
+static DEFAULT_OPTIONS: FieldOptions = FieldOptions {
+    nullable: false,
+    default: None,
+    generator: None,
+    aliases: Vec::new(),
+};
+
+/// The options of struct members, which are always nullable
+pub(crate) static NULLABLE_OPTIONS: FieldOptions = FieldOptions {
+    nullable: true,
+    default: None,
+    generator: None,
+    aliases: Vec::new(),
+};
+
 type FieldTypeList = Vec<(String, ValueType)>;
 
+/// Ensures a value of type `got` can be stored in a field of type `expected`
+/// A type of `None` means the value is null.
+pub(crate) fn typecheck(
+    field: &str,
+    expected: &ValueType,
+    got: &Option<ValueType>,
+    options: &FieldOptions,
+) -> Result<(), SchemaError> {
+    let Some(got) = got else {
+        return if options.nullable {
+            Ok(())
+        } else {
+            Err(SchemaError::NotNullable(field.to_string()))
+        };
+    };
+
+    if expected == got {
+        Ok(())
+    } else {
+        Err(SchemaError::TypeMismatch {
+            field: field.to_string(),
+            expected: expected.clone(),
+            got: got.clone(),
+        })
+    }
+}
+
+/// Converts a value to the representation of its field, if the two differ
+///
+/// Narrow numbers keep their 64-bit type, and are checked when they are encoded.
+/// 64-bit integers are widened for 128-bit fields.
+/// The elements of lists are converted to the element type, and must not be null.
+/// The same holds for the keys and values of maps, which are sorted by key.
+/// Struct members are ordered as declared, and can also be given as a map
+/// with string keys, e.g., a Python dict. Missing members are set to null.
+/// Timestamps are stored in the unit of their field; converting to a coarser
+/// unit rounds towards negative infinity. Decimals are stored with the scale of
+/// their field, and must fit into it without rounding.
+pub(crate) fn convert_value<'v>(
+    field: &str,
+    ftype: &ValueType,
+    value: &'v Value,
+    options: &FieldOptions,
+) -> Result<Cow<'v, Value>, SchemaError> {
+    match (ftype, value) {
+        (ValueType::Timestamp { unit, .. }, Value::Timestamp(ts)) => {
+            if ts.unit() == *unit {
+                Ok(Cow::Borrowed(value))
+            } else {
+                match ts.to_unit(*unit) {
+                    Some(ts) => Ok(Cow::Owned(Value::Timestamp(ts))),
+                    None => Err(SchemaError::OutOfRange(field.to_string())),
+                }
+            }
+        }
+        (ValueType::Decimal { precision, scale }, Value::Decimal(decimal)) => {
+            match decimal.fit_to(*precision, *scale) {
+                Some(decimal) => Ok(Cow::Owned(Value::Decimal(decimal))),
+                None => Err(SchemaError::OutOfRange(field.to_string())),
+            }
+        }
+        (ValueType::List(elem), Value::List(items)) => {
+            let mut converted = Vec::with_capacity(items.len());
+
+            for (idx, item) in items.iter().enumerate() {
+                let name = format!("{}[{}]", field, idx);
+                converted.push(convert_value(&name, elem, item, &DEFAULT_OPTIONS)?.into_owned());
+            }
+
+            Ok(Cow::Owned(Value::List(converted)))
+        }
+        (ValueType::Map(key_type, value_type), Value::Map(entries)) => {
+            let mut converted = Vec::with_capacity(entries.len());
+
+            for (idx, (key, value)) in entries.iter().enumerate() {
+                let key_name = format!("{}.keys[{}]", field, idx);
+                let value_name = format!("{}.values[{}]", field, idx);
+
+                converted.push((
+                    convert_value(&key_name, key_type, key, &DEFAULT_OPTIONS)?.into_owned(),
+                    convert_value(&value_name, value_type, value, &DEFAULT_OPTIONS)?.into_owned(),
+                ));
+            }
+
+            let Some(order) = map::sort_entries(&converted, key_type) else {
+                return Err(SchemaError::OutOfRange(field.to_string()));
+            };
+
+            if order.windows(2).any(|pair| pair[0].0 == pair[1].0) {
+                return Err(SchemaError::DuplicateKey(field.to_string()));
+            }
+
+            let mut converted: Vec<_> = converted.into_iter().map(Some).collect();
+            let sorted = order
+                .into_iter()
+                .map(|(_, idx)| converted[idx].take().unwrap())
+                .collect();
+
+            Ok(Cow::Owned(Value::Map(sorted)))
+        }
+        (ValueType::Struct(fields), Value::Struct(_) | Value::Map(_)) => {
+            let members: Vec<(&str, &Value)> = match value {
+                Value::Struct(members) => members
+                    .iter()
+                    .map(|(name, value)| (name.as_str(), value))
+                    .collect(),
+                Value::Map(entries) => {
+                    let mut members = Vec::with_capacity(entries.len());
+
+                    for (key, value) in entries.iter() {
+                        let Value::String(name) = key else {
+                            return Err(SchemaError::TypeMismatch {
+                                field: field.to_string(),
+                                expected: ftype.clone(),
+                                got: key.get_type().unwrap_or(ValueType::Bytes),
+                            });
+                        };
+                        members.push((name.as_str(), value));
+                    }
+
+                    members
+                }
+                _ => unreachable!(),
+            };
+
+            for (pos, (name, _)) in members.iter().enumerate() {
+                if !fields.iter().any(|(fname, _)| fname == name) {
+                    return Err(SchemaError::NoSuchField(format!("{}.{}", field, name)));
+                }
+                if members[..pos].iter().any(|(other, _)| other == name) {
+                    return Err(SchemaError::DuplicateKey(field.to_string()));
+                }
+            }
+
+            let mut converted = Vec::with_capacity(fields.len());
+
+            for (fname, mtype) in fields.iter() {
+                let member = members
+                    .iter()
+                    .find(|(name, _)| name == fname)
+                    .map_or(&Value::Null, |(_, value)| *value);
+
+                let name = format!("{}.{}", field, fname);
+                let member = convert_value(&name, mtype, member, &NULLABLE_OPTIONS)?;
+                converted.push((fname.clone(), member.into_owned()));
+            }
+
+            Ok(Cow::Owned(Value::Struct(converted)))
+        }
+        (ValueType::Enum(labels), Value::String(label)) => {
+            if labels.contains(label) {
+                Ok(Cow::Borrowed(value))
+            } else {
+                Err(SchemaError::UnknownLabel {
+                    field: field.to_string(),
+                    label: label.clone(),
+                })
+            }
+        }
+        // Integers from Python are I64 or U64 if they fit
+        (ValueType::I128, Value::I64(i)) => Ok(Cow::Owned(Value::I128(*i as i128))),
+        (ValueType::I128, Value::U64(u)) => Ok(Cow::Owned(Value::I128(*u as i128))),
+        (ValueType::U128, Value::U64(u)) => Ok(Cow::Owned(Value::U128(*u as u128))),
+        (ValueType::U128, Value::I64(i)) => match u128::try_from(*i) {
+            Ok(u) => Ok(Cow::Owned(Value::U128(u))),
+            Err(_) => Err(SchemaError::OutOfRange(field.to_string())),
+        },
+        // Narrow fields are range-checked when the value is encoded
+        (ValueType::I8 | ValueType::I16 | ValueType::I32, Value::I64(_))
+        | (ValueType::U8 | ValueType::U16 | ValueType::U32, Value::U64(_))
+        | (ValueType::F32, Value::F64(_)) => Ok(Cow::Borrowed(value)),
+        _ => {
+            typecheck(field, ftype, &value.get_type(), options)?;
+            Ok(Cow::Borrowed(value))
+        }
+    }
+}
+
+/// Checks and converts a value for the given field, and encodes it
+/// Returns None if the value is null.
+pub(crate) fn encode_value(
+    field: &str,
+    ftype: &ValueType,
+    value: &Value,
+    options: &FieldOptions,
+) -> Result<Option<Vec<u8>>, SchemaError> {
+    let value = convert_value(field, ftype, value, options)?;
+
+    if value.is_null() {
+        Ok(None)
+    } else if let Some(bytes) = value.serialize_as(ftype) {
+        Ok(Some(bytes))
+    } else {
+        Err(SchemaError::OutOfRange(field.to_string()))
+    }
+}
+
+/// A field of a schema, resolved once using `Schema::field_handle`
+/// Handles are only meaningful for the schema that created them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct FieldId(usize);
+
+impl FieldId {
+    /// The position of the field in the schema
+    pub fn position(&self) -> usize {
+        self.0
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize)]
+#[serde(from = "SchemaParts")]
 pub struct Schema {
+    #[serde(default)]
+    version: u32,
     key: ValueType,
+    /// The components of a composite key, or empty if the key is a single value
+    #[serde(default)]
+    key_fields: FieldTypeList,
     fields: FieldTypeList,
+    #[serde(default)]
+    options: Vec<FieldOptions>,
+    /// The position of every field by name, which is rebuilt when deserializing
+    #[serde(skip)]
+    index: HashMap<String, usize>,
+}
+
+/// The serialized parts of a schema, from which its index is built
+#[derive(Deserialize)]
+pub(crate) struct SchemaParts {
+    #[serde(default)]
+    pub version: u32,
+    pub key: ValueType,
+    #[serde(default)]
+    pub key_fields: FieldTypeList,
+    pub fields: FieldTypeList,
+    #[serde(default)]
+    pub options: Vec<FieldOptions>,
+}
+
+impl From<SchemaParts> for Schema {
+    fn from(parts: SchemaParts) -> Self {
+        let index = parts
+            .fields
+            .iter()
+            .enumerate()
+            .map(|(pos, (fname, _))| (fname.clone(), pos))
+            .collect();
+
+        Self {
+            version: parts.version,
+            key: parts.key,
+            key_fields: parts.key_fields,
+            fields: parts.fields,
+            options: parts.options,
+            index,
+        }
+    }
 }
 
 impl Schema {
     pub fn from_parts(key: ValueType, fields: FieldTypeList) -> Self {
-        Self { key, fields }
+        let options = vec![FieldOptions::default(); fields.len()];
+
+        SchemaParts {
+            version: 0,
+            key,
+            key_fields: Vec::new(),
+            fields,
+            options,
+        }
+        .into()
+    }
+
+    /// The position of the field called `name`
+    pub(crate) fn position(&self, name: &str) -> Option<usize> {
+        self.index.get(name).copied()
+    }
+
+    /// Resolves the name of a field, so that it can be accessed
+    /// without looking it up again
+    pub fn field_handle(&self, name: &str) -> Result<FieldId, SchemaError> {
+        match self.position(name) {
+            Some(pos) => Ok(FieldId(pos)),
+            None => Err(SchemaError::NoSuchField(name.to_string())),
+        }
+    }
+
+    /// The name of the field that `id` refers to
+    pub fn get_field_name(&self, id: FieldId) -> Result<&str, SchemaError> {
+        match self.fields.get(id.0) {
+            Some((fname, _)) => Ok(fname),
+            None => Err(SchemaError::NoSuchField(format!("#{}", id.0))),
+        }
+    }
+
+    /// Schemas serialized before field options existed have none stored
+    pub(crate) fn field_options(&self, pos: usize) -> &FieldOptions {
+        self.options.get(pos).unwrap_or(&DEFAULT_OPTIONS)
+    }
+
+    pub fn get_field_options(&self, name: &str) -> Result<&FieldOptions, SchemaError> {
+        match self.position(name) {
+            Some(pos) => Ok(self.field_options(pos)),
+            None => Err(SchemaError::NoSuchField(name.to_string())),
+        }
+    }
+
+    /// Attaches a generator to an existing field
+    pub fn set_generator(&mut self, name: &str, generator: Generator) -> Result<(), SchemaError> {
+        let Some(pos) = self.position(name) else {
+            return Err(SchemaError::NoSuchField(name.to_string()));
+        };
+
+        if self.options.len() < self.fields.len() {
+            self.options
+                .resize(self.fields.len(), FieldOptions::default());
+        }
+
+        self.options[pos].generator = Some(generator);
+        Ok(())
+    }
+
+    pub fn get_version(&self) -> u32 {
+        self.version
+    }
+
+    /// Finds the field that `name` refers to
+    ///
+    /// Besides the names of fields, this accepts dotted paths such as
+    /// `address.city` that refer to a member of a struct field. Returns the
+    /// position of the field and the path of the member inside it, if any.
+    fn find_field<'n>(&self, name: &'n str) -> Result<(usize, Option<&'n str>), SchemaError> {
+        if let Some(pos) = self.position(name) {
+            return Ok((pos, None));
+        }
+
+        let no_such_field = || SchemaError::NoSuchField(name.to_string());
+        let (fname, path) = name.split_once('.').ok_or_else(no_such_field)?;
+
+        let pos = self.position(fname).ok_or_else(no_such_field)?;
+
+        let mut vtype = &self.fields[pos].1;
+        for member in path.split('.') {
+            let ValueType::Struct(members) = vtype else {
+                return Err(no_such_field());
+            };
+
+            vtype = members
+                .iter()
+                .find(|(mname, _)| mname == member)
+                .map(|(_, mtype)| mtype)
+                .ok_or_else(no_such_field)?;
+        }
+
+        Ok((pos, Some(path)))
     }
 
+    /// Ensures the entry was written with this version of the schema
+    fn check_entry(&self, entry: &DataEntry) -> Result<(), SchemaError> {
+        if entry.get_version() != self.version {
+            return Err(SchemaError::VersionMismatch {
+                expected: self.version,
+                got: entry.get_version(),
+            });
+        }
+
+        if entry.num_fields() != self.fields.len() {
+            return Err(SchemaError::EncodingError);
+        }
+
+        Ok(())
+    }
+
+    /// The type of the key, or of its first component if the key is composite
     pub fn get_key_type(&self) -> ValueType {
-        self.key
+        self.key.clone()
+    }
+
+    pub fn is_composite_key(&self) -> bool {
+        !self.key_fields.is_empty()
+    }
+
+    /// The names and types of all key components, or an empty list if
+    /// the key is not composite
+    pub fn get_key_fields(&self) -> &FieldTypeList {
+        &self.key_fields
     }
 
     pub fn get_field_types(&self) -> &FieldTypeList {
@@ -69,7 +738,7 @@ impl Schema {
     }
 
     pub fn clone_inner(&self) -> (ValueType, FieldTypeList) {
-        (self.key, self.fields.clone())
+        (self.key.clone(), self.fields.clone())
     }
 
     pub fn set_field(
@@ -78,63 +747,101 @@ impl Schema {
         name: &str,
         value: &Value,
     ) -> Result<(), SchemaError> {
-        if entry.fields.len() != self.fields.len() {
-            return Err(SchemaError::EncodingError);
+        self.check_entry(entry)?;
+
+        let (pos, path) = self.find_field(name)?;
+        let ftype = &self.fields[pos].1;
+        let options = self.field_options(pos);
+
+        if options.generator.is_some() {
+            return Err(SchemaError::GeneratedField(name.to_string()));
         }
 
-        // FIXME typecheck here
+        let bytes = match path {
+            Some(path) => {
+                // Only the struct containing the member is re-encoded
+                let current = (!entry.is_null(pos)).then(|| entry.get_field_bytes(pos));
+                Some(record::update_member(name, ftype, current, path, value)?)
+            }
+            None => encode_value(name, ftype, value, options)?,
+        };
+
+        write_field(entry, pos, ftype, bytes)
+    }
 
-        let bytes = value.serialize_inner();
+    /// Reads a field, or a struct member if `name` is a dotted path
+    /// Members of a struct that is null are null as well.
+    pub fn get_field(&self, entry: &DataEntry, name: &str) -> Result<Value, SchemaError> {
+        self.check_entry(entry)?;
 
-        for (pos, (fname, _)) in self.fields.iter().enumerate() {
-            if fname == name {
-                *entry.fields.get_mut(pos).unwrap() = bytes;
-                return Ok(());
+        match self.find_field(name)? {
+            (pos, None) => read_field(entry, pos, &self.fields[pos].1),
+            (pos, Some(path)) => {
+                let value = read_field_ref(entry, pos, &self.fields[pos].1)?;
+                record::read_member(value, path)?.to_value()
             }
         }
+    }
+
+    /// Same as get_field, but borrows the value from the entry instead of copying it
+    pub fn get_field_ref<'a>(
+        &'a self,
+        entry: &'a DataEntry,
+        name: &str,
+    ) -> Result<ValueRef<'a>, SchemaError> {
+        self.check_entry(entry)?;
+
+        let (pos, path) = self.find_field(name)?;
+        let value = read_field_ref(entry, pos, &self.fields[pos].1)?;
 
-        Err(SchemaError::NoSuchField(name.to_string()))
+        match path {
+            Some(path) => record::read_member(value, path),
+            None => Ok(value),
+        }
     }
 
-    pub fn get_field(&self, entry: &DataEntry, name: &str) -> Result<Value, SchemaError> {
-        if entry.fields.len() != self.fields.len() {
-            return Err(SchemaError::EncodingError);
+    fn get_field_by_handle(&self, id: FieldId) -> Result<&(String, ValueType), SchemaError> {
+        match self.fields.get(id.0) {
+            Some(field) => Ok(field),
+            None => Err(SchemaError::NoSuchField(format!("#{}", id.0))),
         }
+    }
 
-        for (pos, (fname, ftype)) in self.fields.iter().enumerate() {
-            if fname == name {
-                let bytes = entry.fields.get(pos).unwrap();
+    /// Same as get_field, but does not need to look up the field's name
+    pub fn get_field_by_id(&self, entry: &DataEntry, id: FieldId) -> Result<Value, SchemaError> {
+        self.check_entry(entry)?;
 
-                return match Value::from_bytes(bytes, ftype) {
-                    Ok(v) => Ok(v),
-                    Err(_) => {
-                        log::error!("Failed to deserialize field of type {:?}", ftype);
-                        Err(SchemaError::EncodingError)
-                    }
-                };
-            }
+        let (_, ftype) = self.get_field_by_handle(id)?;
+        read_field(entry, id.0, ftype)
+    }
+
+    /// Same as set_field, but does not need to look up the field's name
+    pub fn set_field_by_id(
+        &self,
+        entry: &mut DataEntry,
+        id: FieldId,
+        value: &Value,
+    ) -> Result<(), SchemaError> {
+        self.check_entry(entry)?;
+
+        let (fname, ftype) = self.get_field_by_handle(id)?;
+        let options = self.field_options(id.0);
+
+        if options.generator.is_some() {
+            return Err(SchemaError::GeneratedField(fname.clone()));
         }
 
-        Err(SchemaError::NoSuchField(name.to_string()))
+        let bytes = encode_value(fname, ftype, value, options)?;
+        write_field(entry, id.0, ftype, bytes)
     }
 
     pub fn get_fields(&self, entry: &DataEntry) -> Result<HashMap<String, Value>, SchemaError> {
-        if entry.fields.len() != self.fields.len() {
-            return Err(SchemaError::EncodingError);
-        }
+        self.check_entry(entry)?;
 
         let mut result = HashMap::new();
 
-        for (pos, bytes) in entry.fields.iter().enumerate() {
-            let (name, ftype) = self.fields.get(pos).unwrap();
-
-            let value = match Value::from_bytes(bytes, ftype) {
-                Ok(v) => v,
-                Err(_) => {
-                    log::error!("Failed to deserialize field of type {:?}", ftype);
-                    return Err(SchemaError::EncodingError);
-                }
-            };
+        for (pos, (name, ftype)) in self.fields.iter().enumerate() {
+            let value = read_field(entry, pos, ftype)?;
 
             result.insert(name.clone(), value);
         }
@@ -142,46 +849,32 @@ impl Schema {
         Ok(result)
     }
 
+    /// Reads an entry that only contains the fields in `filter`, in that order
+    /// Use `project` to create such entries and a schema that can read them.
     pub fn get_fields_with_filter(
         &self,
         entry: &DataEntry,
         filter: &[&str],
     ) -> Result<HashMap<String, Value>, SchemaError> {
-        if entry.fields.len() != filter.len() {
+        if entry.get_version() != self.version {
+            return Err(SchemaError::VersionMismatch {
+                expected: self.version,
+                got: entry.get_version(),
+            });
+        }
+
+        if entry.num_fields() != filter.len() {
             return Err(SchemaError::EncodingError);
         }
 
         let mut result = HashMap::new();
-        let mut filter_iter = filter.iter();
-
-        for bytes in entry.fields.iter() {
-            let name = filter_iter
-                .next()
-                .expect("Filter length does not match entry length");
-
-            let ftype = {
-                let mut ftype = None;
-                let mut fpos = 0;
-                while ftype.is_none() {
-                    let (n, t) = self.fields.get(fpos).unwrap();
-
-                    if n == name {
-                        ftype = Some(t);
-                    } else {
-                        fpos += 1;
-                    }
-                }
 
-                ftype.expect("no such field")
+        for (pos, name) in filter.iter().enumerate() {
+            let Some(fpos) = self.position(name) else {
+                return Err(SchemaError::NoSuchField(name.to_string()));
             };
 
-            let value = match Value::from_bytes(bytes, ftype) {
-                Ok(v) => v,
-                Err(_) => {
-                    log::error!("Failed to deserialize field of type {:?}", ftype);
-                    return Err(SchemaError::EncodingError);
-                }
-            };
+            let value = read_field(entry, pos, &self.fields[fpos].1)?;
 
             result.insert(name.to_string(), value);
         }
@@ -191,22 +884,12 @@ impl Schema {
 
     /// Same as get_fields but returns a vector instead
     pub fn get_fields_as_tuple(&self, entry: &DataEntry) -> Result<Tuple, SchemaError> {
-        if entry.fields.len() != self.fields.len() {
-            return Err(SchemaError::EncodingError);
-        }
+        self.check_entry(entry)?;
 
         let mut result = Vec::new();
 
-        for (pos, bytes) in entry.fields.iter().enumerate() {
-            let (name, ftype) = self.fields.get(pos).unwrap();
-
-            let value = match Value::from_bytes(bytes, ftype) {
-                Ok(v) => v,
-                Err(_) => {
-                    log::error!("Failed to deserialize field of type {:?}", ftype);
-                    return Err(SchemaError::EncodingError);
-                }
-            };
+        for (pos, (name, ftype)) in self.fields.iter().enumerate() {
+            let value = read_field(entry, pos, ftype)?;
 
             result.push((name.clone(), value));
         }
@@ -215,7 +898,7 @@ impl Schema {
     }
 
     pub fn build_entry(&self) -> EntryBuilder<'_> {
-        EntryBuilder::new(&self.fields)
+        EntryBuilder::new(self)
     }
 }
 
@@ -264,4 +947,708 @@ mod test {
             42.into()
         );
     }
+
+    #[test]
+    fn type_mismatch() {
+        test_init();
+
+        let schema = SchemaBuilder::new(ValueType::Bool)
+            .add_field("value1", ValueType::String)
+            .build();
+
+        let mut entry = schema.build_entry().set_field("value1", &"foo").build();
+
+        let result = schema.set_field(&mut entry, "value1", &42i64.into());
+        assert_eq!(
+            result,
+            Err(SchemaError::TypeMismatch {
+                field: "value1".to_string(),
+                expected: ValueType::String,
+                got: ValueType::I64,
+            })
+        );
+
+        assert_eq!(schema.get_field(&entry, "value1").unwrap(), "foo".into());
+    }
+
+    #[test]
+    fn try_build_reports_all_errors() {
+        test_init();
+
+        let schema = SchemaBuilder::new(ValueType::Bool)
+            .add_field("value1", ValueType::String)
+            .add_field("value2", ValueType::I64)
+            .add_field("value3", ValueType::Bool)
+            .build();
+
+        let result = schema
+            .build_entry()
+            .set_field("value1", &1.5f64)
+            .set_field("value4", &true)
+            .set_field("value5", &"foo")
+            .try_build();
+
+        // Fields with an invalid value are not reported as missing
+        let expected = EntryError {
+            missing_fields: vec!["value2".to_string(), "value3".to_string()],
+            unknown_fields: vec!["value4".to_string(), "value5".to_string()],
+            invalid_values: vec![SchemaError::TypeMismatch {
+                field: "value1".to_string(),
+                expected: ValueType::String,
+                got: ValueType::F64,
+            }],
+        };
+
+        assert_eq!(result, Err(SchemaError::InvalidEntry(expected)));
+
+        let schema = SchemaBuilder::new(ValueType::Bool)
+            .add_field("list", ValueType::List(Box::new(ValueType::I64)))
+            .build();
+
+        // Unknown fields are not encoded, so values of any shape are reported
+        let mixed = Value::Map(vec![(1u64.into(), true.into()), ("x".into(), false.into())]);
+
+        let result = schema
+            .build_entry()
+            .set_field_from_value("list", &Value::List(vec![1i64.into(), Value::Null]))
+            .set_field_from_value("zzz", &mixed)
+            .try_build();
+
+        let expected = EntryError {
+            missing_fields: vec![],
+            unknown_fields: vec!["zzz".to_string()],
+            invalid_values: vec![SchemaError::NotNullable("list[1]".to_string())],
+        };
+
+        assert_eq!(result, Err(SchemaError::InvalidEntry(expected)));
+        assert_eq!(mixed.try_serialize_inner(), Err(SchemaError::EncodingError));
+    }
+
+    #[test]
+    fn nullable_fields() {
+        test_init();
+
+        let schema = SchemaBuilder::new(ValueType::Bool)
+            .add_field("value1", ValueType::String)
+            .add_nullable_field("value2", ValueType::I64)
+            .add_nullable_field("value3", ValueType::U64)
+            .build();
+
+        let mut entry = schema
+            .build_entry()
+            .set_field("value1", &"foo")
+            .set_field_from_value("value2", &Value::Null)
+            .build();
+
+        assert_eq!(schema.get_field(&entry, "value2").unwrap(), Value::Null);
+        assert_eq!(schema.get_field(&entry, "value3").unwrap(), Value::Null);
+
+        // Null numbers keep their slot, so setting them does not move other fields
+        assert_eq!(entry.get_field_bytes(1).len(), 8);
+        let size = entry.as_bytes().len();
+
+        assert_eq!(
+            schema.set_field(&mut entry, "value1", &Value::Null),
+            Err(SchemaError::NotNullable("value1".to_string()))
+        );
+
+        schema
+            .set_field(&mut entry, "value2", &5i64.into())
+            .unwrap();
+        assert_eq!(schema.get_field(&entry, "value2").unwrap(), 5i64.into());
+        assert_eq!(entry.as_bytes().len(), size);
+
+        schema
+            .set_field(&mut entry, "value3", &Value::Null)
+            .unwrap();
+        let fields = schema.get_fields(&entry).unwrap();
+        assert_eq!(fields.get("value1"), Some(&"foo".into()));
+        assert_eq!(fields.get("value3"), Some(&Value::Null));
+    }
+
+    #[test]
+    fn get_field_ref() {
+        test_init();
+
+        let schema = SchemaBuilder::new(ValueType::Bool)
+            .add_field("value1", ValueType::String)
+            .add_field("value2", ValueType::I64)
+            .add_nullable_field("value3", ValueType::Bool)
+            .build();
+
+        let entry = schema
+            .build_entry()
+            .set_field("value1", &"foobar")
+            .set_field("value2", &-42i64)
+            .build();
+
+        let value1 = schema.get_field_ref(&entry, "value1").unwrap();
+        assert_eq!(value1.as_str(), Some("foobar"));
+        assert_eq!(value1, Value::from("foobar"));
+
+        assert_eq!(
+            schema.get_field_ref(&entry, "value2").unwrap(),
+            ValueRef::I64(-42)
+        );
+        assert!(schema.get_field_ref(&entry, "value3").unwrap().is_null());
+    }
+
+    #[test]
+    fn default_values() {
+        test_init();
+
+        let schema = SchemaBuilder::new(ValueType::Bool)
+            .add_field("value1", ValueType::String)
+            .add_field_with_default("value2", ValueType::I64, 42i64)
+            .add_generated_field("value3", ValueType::U64, |fields| {
+                let Some(Value::String(s)) = fields.get("value1") else {
+                    panic!("value1 not set");
+                };
+                (s.len() as u64).into()
+            })
+            .build();
+
+        let entry = schema.build_entry().set_field("value1", &"foo").build();
+
+        assert_eq!(schema.get_field(&entry, "value2").unwrap(), 42i64.into());
+        assert_eq!(schema.get_field(&entry, "value3").unwrap(), 3u64.into());
+
+        let result = schema
+            .build_entry()
+            .set_field("value1", &"foo")
+            .set_field("value3", &1u64)
+            .try_build();
+        assert!(matches!(result, Err(SchemaError::InvalidEntry(_))));
+
+        let data = bincode::serialize(&schema).unwrap();
+        let schema2: Schema = bincode::deserialize(&data).unwrap();
+
+        assert_eq!(
+            schema2.get_field_options("value2").unwrap().default,
+            Some(42i64.into())
+        );
+        assert_eq!(schema2.get_field_options("value3").unwrap().generator, None);
+    }
+
+    #[test]
+    fn default_type_mismatch() {
+        let result = SchemaBuilder::new(ValueType::Bool)
+            .add_field_with_default("value1", ValueType::String, 42i64)
+            .try_build();
+
+        assert_eq!(
+            result.err(),
+            Some(SchemaError::TypeMismatch {
+                field: "value1".to_string(),
+                expected: ValueType::String,
+                got: ValueType::I64,
+            })
+        );
+    }
+
+    #[test]
+    fn timestamp_fields() {
+        test_init();
+
+        let schema = SchemaBuilder::new(ValueType::Bool)
+            .add_field(
+                "created",
+                ValueType::Timestamp {
+                    unit: TimeUnit::Seconds,
+                    timezone: Some("+02:00".to_string()),
+                },
+            )
+            .add_field("day", ValueType::Date)
+            .add_nullable_field(
+                "precise",
+                ValueType::Timestamp {
+                    unit: TimeUnit::Nanoseconds,
+                    timezone: None,
+                },
+            )
+            .build();
+
+        let created = Timestamp::parse("2024-03-01T10:30:05.750Z").unwrap();
+        let mut entry = schema
+            .build_entry()
+            .set_field_from_value("created", &created.into())
+            .set_field("day", &Date::parse("2024-03-01").unwrap())
+            .build();
+
+        // Stored with the precision of the field
+        let Value::Timestamp(stored) = schema.get_field(&entry, "created").unwrap() else {
+            panic!("Not a timestamp");
+        };
+        assert_eq!(stored.unit(), TimeUnit::Seconds);
+        assert_eq!(
+            stored.to_iso8601(Some("+02:00")).unwrap(),
+            "2024-03-01T12:30:05+02:00"
+        );
+
+        // Converting to a finer unit can overflow
+        let distant = Timestamp::new(i64::MAX, TimeUnit::Seconds);
+        assert_eq!(
+            schema.set_field(&mut entry, "precise", &distant.into()),
+            Err(SchemaError::OutOfRange("precise".to_string()))
+        );
+        schema
+            .set_field(&mut entry, "created", &distant.into())
+            .unwrap();
+
+        let timestamp = Timestamp::new(i64::MIN, TimeUnit::Nanoseconds);
+        assert_eq!(
+            schema.set_field(&mut entry, "day", &timestamp.into()),
+            Err(SchemaError::TypeMismatch {
+                field: "day".to_string(),
+                expected: ValueType::Date,
+                got: ValueType::Timestamp {
+                    unit: TimeUnit::Nanoseconds,
+                    timezone: None
+                },
+            })
+        );
+
+        let result = SchemaBuilder::new(ValueType::Bool)
+            .add_field(
+                "created",
+                ValueType::Timestamp {
+                    unit: TimeUnit::Seconds,
+                    timezone: Some("Europe/Berlin".to_string()),
+                },
+            )
+            .try_build();
+        assert_eq!(
+            result.err(),
+            Some(SchemaError::InvalidTimezone("Europe/Berlin".to_string()))
+        );
+    }
+
+    #[test]
+    fn narrow_fields() {
+        test_init();
+
+        let schema = SchemaBuilder::new(ValueType::Bool)
+            .add_field("small", ValueType::I16)
+            .add_field("counter", ValueType::U8)
+            .add_field("ratio", ValueType::F32)
+            .build();
+
+        let mut entry = schema
+            .build_entry()
+            .set_field_from_value("small", &(-300i64).into())
+            .set_field("counter", &7u8)
+            .set_field_from_value("ratio", &0.5.into())
+            .build();
+
+        assert_eq!(entry.get_field_bytes(0).len(), 2);
+        assert_eq!(entry.get_field_bytes(1).len(), 1);
+        assert_eq!(entry.get_field_bytes(2).len(), 4);
+
+        assert_eq!(schema.get_field(&entry, "small").unwrap(), Value::I64(-300));
+        assert_eq!(schema.get_field(&entry, "counter").unwrap(), Value::U64(7));
+        assert_eq!(schema.get_field(&entry, "ratio").unwrap(), Value::F64(0.5));
+
+        assert_eq!(
+            schema.set_field(&mut entry, "counter", &300u64.into()),
+            Err(SchemaError::OutOfRange("counter".to_string()))
+        );
+        assert!(matches!(
+            schema.set_field(&mut entry, "small", &300u64.into()),
+            Err(SchemaError::TypeMismatch { .. })
+        ));
+
+        // The generic path agrees with the Value path
+        let schema = SchemaBuilder::new(ValueType::Bool)
+            .add_field("wide", ValueType::I64)
+            .add_field("small", ValueType::I16)
+            .build();
+
+        for value in [5i32, 70_000] {
+            let generic = schema
+                .build_entry()
+                .set_field("wide", &value)
+                .set_field("small", &value)
+                .try_build();
+            let converted = schema
+                .build_entry()
+                .set_field_from_value("wide", &value.into())
+                .set_field_from_value("small", &value.into())
+                .try_build();
+
+            assert_eq!(generic, converted);
+        }
+
+        let entry = schema
+            .build_entry()
+            .set_field("wide", &5i32)
+            .set_field("small", &-7i64)
+            .build();
+        assert_eq!(schema.get_field(&entry, "wide").unwrap(), Value::I64(5));
+        assert_eq!(schema.get_field(&entry, "small").unwrap(), Value::I64(-7));
+        assert!(matches!(
+            schema.build_entry().set_field("small", &1.5f32).try_build(),
+            Err(SchemaError::InvalidEntry(_))
+        ));
+    }
+
+    #[test]
+    fn wide_integer_fields() {
+        test_init();
+
+        let schema = SchemaBuilder::new(ValueType::Bool)
+            .add_field("hash", ValueType::U128)
+            .add_field("total", ValueType::I128)
+            .build();
+
+        let mut entry = schema
+            .build_entry()
+            .set_field("hash", &u128::MAX)
+            .set_field_from_value("total", &(-5i64).into())
+            .build();
+
+        assert_eq!(schema.get_field(&entry, "hash").unwrap(), u128::MAX.into());
+        assert_eq!(schema.get_field(&entry, "total").unwrap(), (-5i128).into());
+
+        schema.set_field(&mut entry, "hash", &7i64.into()).unwrap();
+        assert_eq!(schema.get_field(&entry, "hash").unwrap(), 7u128.into());
+        assert_eq!(
+            schema.set_field(&mut entry, "hash", &(-7i64).into()),
+            Err(SchemaError::OutOfRange("hash".to_string()))
+        );
+    }
+
+    #[test]
+    fn list_fields() {
+        test_init();
+
+        let schema = SchemaBuilder::new(ValueType::Bool)
+            .add_field("scores", ValueType::List(Box::new(ValueType::U8)))
+            .add_field("tags", ValueType::List(Box::new(ValueType::String)))
+            .build();
+
+        let mut entry = schema
+            .build_entry()
+            .set_field_from_value("scores", &Value::List(vec![1u64.into(), 2u64.into()]))
+            .set_field_from_value("tags", &Value::List(vec!["a".into(), "b".into()]))
+            .build();
+
+        let tags = schema.get_field_ref(&entry, "tags").unwrap();
+        let tags = tags.as_list().unwrap();
+        assert_eq!(tags.len(), 2);
+        assert_eq!(tags.get(1).unwrap().unwrap().as_str(), Some("b"));
+
+        assert_eq!(
+            schema.get_field(&entry, "scores").unwrap(),
+            Value::List(vec![1u64.into(), 2u64.into()])
+        );
+
+        assert_eq!(
+            schema.set_field(
+                &mut entry,
+                "tags",
+                &Value::List(vec!["a".into(), Value::Null])
+            ),
+            Err(SchemaError::NotNullable("tags[1]".to_string()))
+        );
+        assert_eq!(
+            schema.set_field(&mut entry, "tags", &Value::List(vec![1i64.into()])),
+            Err(SchemaError::TypeMismatch {
+                field: "tags[0]".to_string(),
+                expected: ValueType::String,
+                got: ValueType::I64,
+            })
+        );
+        assert_eq!(
+            schema.set_field(&mut entry, "scores", &Value::List(vec![256u64.into()])),
+            Err(SchemaError::OutOfRange("scores".to_string()))
+        );
+    }
+
+    #[test]
+    fn map_fields() {
+        test_init();
+
+        let counts = ValueType::Map(Box::new(ValueType::String), Box::new(ValueType::U32));
+        let schema = SchemaBuilder::new(ValueType::Bool)
+            .add_field("counts", counts)
+            .build();
+
+        let entry1 = schema
+            .build_entry()
+            .set_field_from_value(
+                "counts",
+                &Value::Map(vec![("b".into(), 2u64.into()), ("a".into(), 1u64.into())]),
+            )
+            .build();
+        let mut entry2 = schema
+            .build_entry()
+            .set_field_from_value(
+                "counts",
+                &Value::Map(vec![("a".into(), 1u64.into()), ("b".into(), 2u64.into())]),
+            )
+            .build();
+
+        // Equal maps are encoded the same way, regardless of insertion order
+        assert_eq!(entry1, entry2);
+        assert_eq!(
+            schema.get_field(&entry1, "counts").unwrap(),
+            Value::Map(vec![("a".into(), 1u64.into()), ("b".into(), 2u64.into())])
+        );
+
+        let counts = schema.get_field_ref(&entry1, "counts").unwrap();
+        let counts = counts.as_map().unwrap();
+        assert_eq!(counts.len(), 2);
+        assert_eq!(counts.get(&"b".into()).unwrap(), Some(ValueRef::U64(2)));
+        assert_eq!(counts.get(&"c".into()).unwrap(), None);
+
+        assert_eq!(
+            schema.set_field(
+                &mut entry2,
+                "counts",
+                &Value::Map(vec![("a".into(), 1u64.into()), ("a".into(), 2u64.into())])
+            ),
+            Err(SchemaError::DuplicateKey("counts".to_string()))
+        );
+        assert_eq!(
+            schema.set_field(
+                &mut entry2,
+                "counts",
+                &Value::Map(vec![("a".into(), Value::Null)])
+            ),
+            Err(SchemaError::NotNullable("counts.values[0]".to_string()))
+        );
+
+        let result = SchemaBuilder::new(ValueType::Bool)
+            .add_field(
+                "invalid",
+                ValueType::Map(
+                    Box::new(ValueType::List(Box::new(ValueType::U8))),
+                    Box::new(ValueType::U8),
+                ),
+            )
+            .try_build();
+        assert!(matches!(result, Err(SchemaError::UnsupportedKeyType(_))));
+    }
+
+    #[test]
+    fn field_handles() {
+        test_init();
+
+        let schema = SchemaBuilder::new(ValueType::Bool)
+            .add_field("a", ValueType::U64)
+            .add_field("b", ValueType::String)
+            .build();
+
+        let handle = schema.field_handle("b").unwrap();
+        assert_eq!(handle.position(), 1);
+        assert_eq!(schema.get_field_name(handle).unwrap(), "b");
+        assert_eq!(
+            schema.field_handle("c"),
+            Err(SchemaError::NoSuchField("c".to_string()))
+        );
+
+        let mut entry = schema
+            .build_entry()
+            .set_field("a", &1u64)
+            .set_field("b", &"foo")
+            .build();
+
+        assert_eq!(
+            schema.get_field_by_id(&entry, handle).unwrap(),
+            "foo".into()
+        );
+        schema
+            .set_field_by_id(&mut entry, handle, &"bar".into())
+            .unwrap();
+        assert_eq!(schema.get_field(&entry, "b").unwrap(), "bar".into());
+        assert!(matches!(
+            schema.set_field_by_id(&mut entry, handle, &1u64.into()),
+            Err(SchemaError::TypeMismatch { .. })
+        ));
+
+        // The index is rebuilt when a schema is deserialized
+        let data = bincode::serialize(&schema).unwrap();
+        let schema: Schema = bincode::deserialize(&data).unwrap();
+        assert_eq!(schema.field_handle("b"), Ok(handle));
+    }
+
+    #[test]
+    fn enum_fields() {
+        test_init();
+
+        let status = ValueType::Enum(vec!["active".to_string(), "closed".to_string()]);
+        let schema = SchemaBuilder::new(ValueType::Bool)
+            .add_field("status", status)
+            .build();
+
+        let mut entry = schema
+            .build_entry()
+            .set_field_from_value("status", &"closed".into())
+            .build();
+
+        assert_eq!(entry.get_field_bytes(0), &[1]);
+        assert_eq!(schema.get_field(&entry, "status").unwrap(), "closed".into());
+        assert_eq!(
+            schema.get_field_ref(&entry, "status").unwrap(),
+            ValueRef::String("closed")
+        );
+
+        assert_eq!(
+            schema.set_field(&mut entry, "status", &"pending".into()),
+            Err(SchemaError::UnknownLabel {
+                field: "status".to_string(),
+                label: "pending".to_string(),
+            })
+        );
+
+        let result = SchemaBuilder::new(ValueType::Bool)
+            .add_field(
+                "status",
+                ValueType::Enum(vec!["a".to_string(), "a".to_string()]),
+            )
+            .try_build();
+        assert!(matches!(result, Err(SchemaError::InvalidType(_))));
+    }
+
+    #[test]
+    fn struct_fields() {
+        test_init();
+
+        let address = ValueType::Struct(vec![
+            ("street".to_string(), ValueType::String),
+            ("city".to_string(), ValueType::String),
+        ]);
+        let schema = SchemaBuilder::new(ValueType::Bool)
+            .add_field("address", address)
+            .build();
+
+        let mut entry = schema
+            .build_entry()
+            .set_field_from_value(
+                "address",
+                &Value::Struct(vec![("city".to_string(), "Zurich".into())]),
+            )
+            .build();
+
+        assert_eq!(
+            schema.get_field(&entry, "address.city").unwrap(),
+            "Zurich".into()
+        );
+        assert_eq!(
+            schema.get_field(&entry, "address.street").unwrap(),
+            Value::Null
+        );
+
+        schema
+            .set_field(&mut entry, "address.street", &"Main St".into())
+            .unwrap();
+        assert_eq!(
+            schema.get_field_ref(&entry, "address.street").unwrap(),
+            ValueRef::String("Main St")
+        );
+        assert_eq!(
+            schema.get_field(&entry, "address").unwrap(),
+            Value::Struct(vec![
+                ("street".to_string(), "Main St".into()),
+                ("city".to_string(), "Zurich".into()),
+            ])
+        );
+
+        assert_eq!(
+            schema.set_field(&mut entry, "address.city", &1i64.into()),
+            Err(SchemaError::TypeMismatch {
+                field: "address.city".to_string(),
+                expected: ValueType::String,
+                got: ValueType::I64,
+            })
+        );
+        assert_eq!(
+            schema.get_field(&entry, "address.zip"),
+            Err(SchemaError::NoSuchField("address.zip".to_string()))
+        );
+        assert_eq!(
+            schema.set_field(
+                &mut entry,
+                "address",
+                &Value::Map(vec![("zip".into(), "8000".into())])
+            ),
+            Err(SchemaError::NoSuchField("address.zip".to_string()))
+        );
+        assert_eq!(
+            schema.set_field(
+                &mut entry,
+                "address",
+                &Value::Map(vec![(5u64.into(), "8000".into())])
+            ),
+            Err(SchemaError::TypeMismatch {
+                field: "address".to_string(),
+                expected: schema.get_field_types()[0].1.clone(),
+                got: ValueType::U64,
+            })
+        );
+
+        // Dots are reserved for member paths
+        let dotted = ValueType::Struct(vec![("zip.code".to_string(), ValueType::String)]);
+        let result = SchemaBuilder::new(ValueType::Bool)
+            .add_field("address", dotted.clone())
+            .try_build();
+        assert_eq!(result.err(), Some(SchemaError::InvalidType(dotted)));
+
+        let result = SchemaBuilder::new(ValueType::Bool)
+            .add_field("address.city", ValueType::String)
+            .try_build();
+        assert_eq!(
+            result.err(),
+            Some(SchemaError::InvalidType(ValueType::String))
+        );
+    }
+
+    #[test]
+    fn decimal_fields() {
+        test_init();
+
+        let price = ValueType::Decimal {
+            precision: 6,
+            scale: 2,
+        };
+        let schema = SchemaBuilder::new(ValueType::Bool)
+            .add_field("price", price.clone())
+            .build();
+
+        let mut entry = schema
+            .build_entry()
+            .set_field_from_value("price", &Decimal::parse("12.5").unwrap().into())
+            .build();
+
+        let Value::Decimal(stored) = schema.get_field(&entry, "price").unwrap() else {
+            panic!("Not a decimal");
+        };
+        assert_eq!(stored.to_string(), "12.50");
+
+        for input in ["0.125", "10000"] {
+            assert_eq!(
+                schema.set_field(&mut entry, "price", &Decimal::parse(input).unwrap().into()),
+                Err(SchemaError::OutOfRange("price".to_string()))
+            );
+        }
+
+        let invalid = ValueType::Decimal {
+            precision: 2,
+            scale: 3,
+        };
+        let result = SchemaBuilder::new(ValueType::Bool)
+            .add_field("price", invalid.clone())
+            .try_build();
+        assert_eq!(result.err(), Some(SchemaError::InvalidType(invalid)));
+    }
+
+    #[test]
+    #[should_panic(expected = "Type mismatch for field value1")]
+    fn build_type_mismatch() {
+        let schema = SchemaBuilder::new(ValueType::Bool)
+            .add_field("value1", ValueType::String)
+            .build();
+
+        let _ = schema.build_entry().set_field("value1", &42u64).build();
+    }
 }
@@ -0,0 +1,161 @@
+//! Encoding of list fields
+//!
+//! A list is stored as its length (u32, little-endian) followed by its elements.
+//! Elements of fixed-size types, such as integers, are packed back to back.
+//! All other elements are prefixed with their encoded length (u32, little-endian).
+
+use crate::{SchemaError, Value, ValueRef, ValueType};
+
+pub(crate) fn read_u32(data: &[u8]) -> Result<(usize, &[u8]), SchemaError> {
+    if data.len() < 4 {
+        return Err(SchemaError::EncodingError);
+    }
+
+    let (len, rest) = data.split_at(4);
+    Ok((u32::from_le_bytes(len.try_into().unwrap()) as usize, rest))
+}
+
+/// Appends a length to `buf`, or returns None if it does not fit into a u32
+pub(crate) fn push_u32(len: usize, buf: &mut Vec<u8>) -> Option<()> {
+    buf.extend_from_slice(&u32::try_from(len).ok()?.to_le_bytes());
+    Some(())
+}
+
+/// Appends an element encoded by `Value::serialize_as` to `buf`
+/// Returns None if the element is too large to be prefixed with its length.
+pub(crate) fn push_element(bytes: &[u8], elem: &ValueType, buf: &mut Vec<u8>) -> Option<()> {
+    if elem.fixed_size().is_none() {
+        push_u32(bytes.len(), buf)?;
+    }
+    buf.extend_from_slice(bytes);
+    Some(())
+}
+
+/// Splits the next element of type `elem` off `data`
+pub(crate) fn split_element<'a>(
+    data: &mut &'a [u8],
+    elem: &ValueType,
+) -> Result<&'a [u8], SchemaError> {
+    let size = match elem.fixed_size() {
+        Some(size) => size,
+        None => {
+            let (size, rest) = read_u32(data)?;
+            *data = rest;
+            size
+        }
+    };
+
+    if data.len() < size {
+        return Err(SchemaError::EncodingError);
+    }
+
+    let (element, rest) = data.split_at(size);
+    *data = rest;
+    Ok(element)
+}
+
+/// Encodes the items of a list as elements of type `elem`
+/// Returns None if an item does not fit into the element type, or the list is too long.
+pub(crate) fn serialize_list(items: &[Value], elem: &ValueType) -> Option<Vec<u8>> {
+    let mut buf = Vec::new();
+    push_u32(items.len(), &mut buf)?;
+
+    for item in items {
+        push_element(&item.serialize_as(elem)?, elem, &mut buf)?;
+    }
+
+    Some(buf)
+}
+
+/// A list borrowed from the encoded fields of a `DataEntry`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ListRef<'a> {
+    elem: &'a ValueType,
+    len: usize,
+    data: &'a [u8],
+}
+
+impl<'a> ListRef<'a> {
+    pub(crate) fn from_bytes(data: &'a [u8], elem: &'a ValueType) -> Result<Self, SchemaError> {
+        let (len, data) = read_u32(data)?;
+
+        if let Some(size) = elem.fixed_size() {
+            if len.checked_mul(size) != Some(data.len()) {
+                return Err(SchemaError::EncodingError);
+            }
+        }
+
+        Ok(Self { elem, len, data })
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn element_type(&self) -> &'a ValueType {
+        self.elem
+    }
+
+    /// Reads the element at `idx`, which takes constant time for fixed-size elements
+    pub fn get(&self, idx: usize) -> Result<Option<ValueRef<'a>>, SchemaError> {
+        if idx >= self.len {
+            return Ok(None);
+        }
+
+        if let Some(size) = self.elem.fixed_size() {
+            let data = &self.data[idx * size..(idx + 1) * size];
+            return ValueRef::from_bytes(data, self.elem).map(Some);
+        }
+
+        self.iter().nth(idx).transpose()
+    }
+
+    pub fn iter(&self) -> ListIter<'a> {
+        ListIter {
+            elem: self.elem,
+            remaining: self.len,
+            data: self.data,
+        }
+    }
+
+    /// Copies the list into an owned `Value::List`
+    pub fn to_value(&self) -> Result<Value, SchemaError> {
+        let items = self
+            .iter()
+            .map(|item| item.and_then(|item| item.to_value()))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Value::List(items))
+    }
+}
+
+pub struct ListIter<'a> {
+    elem: &'a ValueType,
+    remaining: usize,
+    data: &'a [u8],
+}
+
+impl<'a> Iterator for ListIter<'a> {
+    type Item = Result<ValueRef<'a>, SchemaError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+        self.remaining -= 1;
+
+        let result = split_element(&mut self.data, self.elem)
+            .and_then(|data| ValueRef::from_bytes(data, self.elem));
+
+        // Stop after the first error, as the remaining elements cannot be found
+        if result.is_err() {
+            self.remaining = 0;
+        }
+
+        Some(result)
+    }
+}
@@ -0,0 +1,160 @@
+//! Encoding of map fields
+//!
+//! A map is stored as its number of entries (u32, little-endian) followed by
+//! each key and its value, using the same element encoding as lists.
+//! Entries are sorted by the order-preserving encoding of their keys, so that
+//! equal maps always produce equal bytes.
+
+use crate::key::encode_key;
+use crate::list::{push_element, push_u32, read_u32, split_element};
+use crate::{SchemaError, Value, ValueRef, ValueType};
+
+/// Sorts map entries by their key, as they are stored
+/// Returns None if a key cannot be encoded as `key_type`.
+pub(crate) fn sort_entries(
+    entries: &[(Value, Value)],
+    key_type: &ValueType,
+) -> Option<Vec<(Vec<u8>, usize)>> {
+    let mut keys = Vec::with_capacity(entries.len());
+
+    for (idx, (key, _)) in entries.iter().enumerate() {
+        keys.push((encode_key(key, key_type).ok()?, idx));
+    }
+
+    keys.sort();
+    Some(keys)
+}
+
+/// Encodes the entries of a map in sorted order
+/// Returns None if a key or value does not fit into its type.
+pub(crate) fn serialize_map(
+    entries: &[(Value, Value)],
+    key_type: &ValueType,
+    value_type: &ValueType,
+) -> Option<Vec<u8>> {
+    let mut buf = Vec::new();
+    push_u32(entries.len(), &mut buf)?;
+
+    for (_, idx) in sort_entries(entries, key_type)? {
+        let (key, value) = &entries[idx];
+        push_element(&key.serialize_as(key_type)?, key_type, &mut buf)?;
+        push_element(&value.serialize_as(value_type)?, value_type, &mut buf)?;
+    }
+
+    Some(buf)
+}
+
+/// A map borrowed from the encoded fields of a `DataEntry`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MapRef<'a> {
+    key_type: &'a ValueType,
+    value_type: &'a ValueType,
+    len: usize,
+    data: &'a [u8],
+}
+
+impl<'a> MapRef<'a> {
+    pub(crate) fn from_bytes(
+        data: &'a [u8],
+        key_type: &'a ValueType,
+        value_type: &'a ValueType,
+    ) -> Result<Self, SchemaError> {
+        let (len, data) = read_u32(data)?;
+
+        Ok(Self {
+            key_type,
+            value_type,
+            len,
+            data,
+        })
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn key_type(&self) -> &'a ValueType {
+        self.key_type
+    }
+
+    pub fn value_type(&self) -> &'a ValueType {
+        self.value_type
+    }
+
+    /// Looks up the value stored for `key`
+    pub fn get(&self, key: &Value) -> Result<Option<ValueRef<'a>>, SchemaError> {
+        for entry in self.iter() {
+            let (entry_key, value) = entry?;
+            if entry_key == *key {
+                return Ok(Some(value));
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// Iterates over all entries, ordered by key
+    pub fn iter(&self) -> MapIter<'a> {
+        MapIter {
+            key_type: self.key_type,
+            value_type: self.value_type,
+            remaining: self.len,
+            data: self.data,
+        }
+    }
+
+    /// Copies the map into an owned `Value::Map`
+    pub fn to_value(&self) -> Result<Value, SchemaError> {
+        let mut entries = Vec::with_capacity(self.len);
+
+        for entry in self.iter() {
+            let (key, value) = entry?;
+            entries.push((key.to_value()?, value.to_value()?));
+        }
+
+        Ok(Value::Map(entries))
+    }
+}
+
+pub struct MapIter<'a> {
+    key_type: &'a ValueType,
+    value_type: &'a ValueType,
+    remaining: usize,
+    data: &'a [u8],
+}
+
+impl<'a> MapIter<'a> {
+    fn next_entry(&mut self) -> Result<(ValueRef<'a>, ValueRef<'a>), SchemaError> {
+        let key = split_element(&mut self.data, self.key_type)?;
+        let value = split_element(&mut self.data, self.value_type)?;
+
+        Ok((
+            ValueRef::from_bytes(key, self.key_type)?,
+            ValueRef::from_bytes(value, self.value_type)?,
+        ))
+    }
+}
+
+impl<'a> Iterator for MapIter<'a> {
+    type Item = Result<(ValueRef<'a>, ValueRef<'a>), SchemaError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+        self.remaining -= 1;
+
+        let result = self.next_entry();
+
+        // Stop after the first error, as the remaining entries cannot be found
+        if result.is_err() {
+            self.remaining = 0;
+        }
+
+        Some(result)
+    }
+}
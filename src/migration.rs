@@ -0,0 +1,264 @@
+use crate::compat::find_source_field;
+use crate::{read_field, DataEntry, Schema, SchemaError, Value};
+
+use std::borrow::Cow;
+use std::collections::HashMap;
+
+impl Schema {
+    /// Converts an entry written with the `old` schema to the layout of this schema
+    ///
+    /// Fields are matched by name or by one of their aliases. Fields that are
+    /// not part of the old schema are set to their default value, or null.
+    /// Fields that are not part of this schema are dropped, and values
+    /// of the old type are widened to the new type, if needed.
+    pub fn migrate(&self, old: &Schema, entry: &DataEntry) -> Result<DataEntry, SchemaError> {
+        old.check_entry(entry)?;
+
+        let mut builder = self.build_entry();
+
+        for (pos, (fname, ftype)) in self.fields.iter().enumerate() {
+            let options = self.field_options(pos);
+
+            // Generated fields are always re-computed
+            if options.generator.is_some() {
+                continue;
+            }
+
+            let Some(old_pos) = find_source_field(self, pos, old) else {
+                continue;
+            };
+
+            let old_type = &old.fields[old_pos].1;
+            let value = read_field(entry, old_pos, old_type)?;
+
+            let widened = if old_type.can_widen_to(ftype) {
+                value.widen(ftype)
+            } else {
+                None
+            };
+
+            let Some(value) = widened else {
+                return Err(SchemaError::TypeMismatch {
+                    field: fname.clone(),
+                    expected: ftype.clone(),
+                    got: old_type.clone(),
+                });
+            };
+
+            builder = builder.set_field_from_value(fname, &value);
+        }
+
+        builder.try_build()
+    }
+}
+
+/// All versions of a schema, so that entries can be migrated lazily when read
+pub struct SchemaHistory {
+    /// Ordered by version, with the current schema last
+    schemas: Vec<Schema>,
+}
+
+impl SchemaHistory {
+    pub fn new(schema: Schema) -> Self {
+        Self {
+            schemas: vec![schema],
+        }
+    }
+
+    /// Makes `schema` the current version
+    pub fn push(&mut self, schema: Schema) {
+        if schema.version <= self.current().version {
+            panic!(
+                "Schema version {} is not newer than {}",
+                schema.version,
+                self.current().version
+            );
+        }
+
+        self.schemas.push(schema);
+    }
+
+    pub fn current(&self) -> &Schema {
+        self.schemas.last().unwrap()
+    }
+
+    pub fn get_version(&self, version: u32) -> Option<&Schema> {
+        self.schemas
+            .binary_search_by_key(&version, |schema| schema.version)
+            .ok()
+            .map(|pos| &self.schemas[pos])
+    }
+
+    /// Converts the entry to the current version of the schema,
+    /// going through all versions in between
+    pub fn migrate<'a>(&self, entry: &'a DataEntry) -> Result<Cow<'a, DataEntry>, SchemaError> {
+        if entry.get_version() == self.current().version {
+            return Ok(Cow::Borrowed(entry));
+        }
+
+        let Ok(start) = self
+            .schemas
+            .binary_search_by_key(&entry.get_version(), |schema| schema.version)
+        else {
+            return Err(SchemaError::UnknownVersion(entry.get_version()));
+        };
+
+        let mut entry = Cow::Borrowed(entry);
+
+        for versions in self.schemas[start..].windows(2) {
+            let (old, new) = (&versions[0], &versions[1]);
+            entry = Cow::Owned(new.migrate(old, &entry)?);
+        }
+
+        Ok(entry)
+    }
+
+    /// Reads a field using the current schema, migrating the entry if needed
+    pub fn get_field(&self, entry: &DataEntry, name: &str) -> Result<Value, SchemaError> {
+        let entry = self.migrate(entry)?;
+        self.current().get_field(&entry, name)
+    }
+
+    /// Reads all fields using the current schema, migrating the entry if needed
+    pub fn get_fields(&self, entry: &DataEntry) -> Result<HashMap<String, Value>, SchemaError> {
+        let entry = self.migrate(entry)?;
+        self.current().get_fields(&entry)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{SchemaBuilder, SchemaError, SchemaHistory, Value, ValueType};
+
+    #[test]
+    fn migrate_entry() {
+        let v1 = SchemaBuilder::new(ValueType::U64)
+            .set_version(1)
+            .add_field("count", ValueType::I64)
+            .add_field("label", ValueType::String)
+            .add_field("flag", ValueType::Bool)
+            .build();
+
+        let entry = v1
+            .build_entry()
+            .set_field("count", &5i64)
+            .set_field("label", &"foo")
+            .set_field("flag", &true)
+            .build();
+
+        let v2 = SchemaBuilder::new(ValueType::U64)
+            .set_version(2)
+            .add_field("count", ValueType::F64)
+            .add_field("name", ValueType::String)
+            .add_alias("name", "label")
+            .add_field_with_default("extra", ValueType::U64, 7u64)
+            .build();
+
+        let v3 = SchemaBuilder::new(ValueType::U64)
+            .set_version(3)
+            .add_field("count", ValueType::F64)
+            .add_field("name", ValueType::String)
+            .add_field("extra", ValueType::U64)
+            .add_nullable_field("comment", ValueType::String)
+            .build();
+
+        assert_eq!(
+            v3.get_field(&entry, "count"),
+            Err(SchemaError::VersionMismatch {
+                expected: 3,
+                got: 1
+            })
+        );
+
+        let mut history = SchemaHistory::new(v1);
+        history.push(v2);
+        history.push(v3);
+
+        let migrated = history.migrate(&entry).unwrap();
+        assert_eq!(migrated.get_version(), 3);
+
+        let fields = history.current().get_fields_as_tuple(&migrated).unwrap();
+        assert_eq!(
+            fields,
+            vec![
+                ("count".to_string(), 5.0.into()),
+                ("name".to_string(), "foo".into()),
+                ("extra".to_string(), 7u64.into()),
+                ("comment".to_string(), crate::Value::Null),
+            ]
+        );
+    }
+
+    #[test]
+    fn migrate_nested() {
+        let v1 = SchemaBuilder::new(ValueType::U64)
+            .add_field(
+                "point",
+                ValueType::Struct(vec![
+                    ("x".to_string(), ValueType::I32),
+                    ("label".to_string(), ValueType::String),
+                ]),
+            )
+            .build();
+
+        let v2 = SchemaBuilder::new(ValueType::U64)
+            .set_version(1)
+            .add_field(
+                "point",
+                ValueType::Struct(vec![
+                    ("x".to_string(), ValueType::I64),
+                    ("y".to_string(), ValueType::I64),
+                ]),
+            )
+            .build();
+
+        let entry = v1
+            .build_entry()
+            .set_field_from_value(
+                "point",
+                &Value::Struct(vec![
+                    ("x".to_string(), 3i64.into()),
+                    ("label".to_string(), "a".into()),
+                ]),
+            )
+            .build();
+
+        // Members are widened, added as null or dropped
+        let migrated = v2.migrate(&v1, &entry).unwrap();
+        assert_eq!(v2.get_field(&migrated, "point.x").unwrap(), 3i64.into());
+        assert_eq!(v2.get_field(&migrated, "point.y").unwrap(), Value::Null);
+
+        let v3 = SchemaBuilder::new(ValueType::U64)
+            .set_version(2)
+            .add_field(
+                "point",
+                ValueType::Struct(vec![("x".to_string(), ValueType::String)]),
+            )
+            .build();
+
+        assert!(matches!(
+            v3.migrate(&v2, &migrated),
+            Err(SchemaError::TypeMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn migrate_missing_default() {
+        let v1 = SchemaBuilder::new(ValueType::U64)
+            .add_field("count", ValueType::I64)
+            .build();
+
+        let v2 = SchemaBuilder::new(ValueType::U64)
+            .set_version(1)
+            .add_field("count", ValueType::I64)
+            .add_field("extra", ValueType::U64)
+            .build();
+
+        let entry = v1.build_entry().set_field("count", &5i64).build();
+
+        assert!(matches!(
+            v2.migrate(&v1, &entry),
+            Err(SchemaError::InvalidEntry(_))
+        ));
+    }
+}
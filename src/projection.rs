@@ -0,0 +1,153 @@
+use crate::{DataEntry, Schema, SchemaError, SchemaParts};
+
+/// Turns entries of a schema into entries that only contain some of its fields
+/// Created by `Schema::project`.
+#[derive(Debug, Clone)]
+pub struct Projector {
+    version: u32,
+    num_fields: usize,
+    /// The position of each projected field in the full entry
+    positions: Vec<usize>,
+}
+
+impl Projector {
+    /// Copies the projected fields of `entry` into a new entry
+    /// The fields are copied as-is without decoding them.
+    pub fn project(&self, entry: &DataEntry) -> Result<DataEntry, SchemaError> {
+        if entry.get_version() != self.version {
+            return Err(SchemaError::VersionMismatch {
+                expected: self.version,
+                got: entry.get_version(),
+            });
+        }
+
+        if entry.num_fields() != self.num_fields {
+            return Err(SchemaError::EncodingError);
+        }
+
+        let fields = self
+            .positions
+            .iter()
+            .map(|&pos| (entry.get_field_bytes(pos).to_vec(), entry.is_null(pos)))
+            .collect();
+
+        DataEntry::from_parts(self.version, fields)
+    }
+
+    /// The number of fields in projected entries
+    pub fn len(&self) -> usize {
+        self.positions.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.positions.is_empty()
+    }
+}
+
+impl Schema {
+    /// Creates a schema that only contains the given fields, in the given order
+    ///
+    /// The returned projector converts entries of this schema to entries of
+    /// the new schema, e.g., to only send the fields a client asked for.
+    /// The new schema keeps the key and version of this schema.
+    pub fn project(&self, names: &[&str]) -> Result<(Schema, Projector), SchemaError> {
+        let mut positions = Vec::with_capacity(names.len());
+
+        for (idx, name) in names.iter().enumerate() {
+            let Some(pos) = self.position(name) else {
+                return Err(SchemaError::NoSuchField(name.to_string()));
+            };
+
+            if names[..idx].contains(name) {
+                return Err(SchemaError::DuplicateField(name.to_string()));
+            }
+
+            positions.push(pos);
+        }
+
+        let parts = SchemaParts {
+            version: self.version,
+            key: self.key.clone(),
+            key_fields: self.key_fields.clone(),
+            fields: positions
+                .iter()
+                .map(|&pos| self.fields[pos].clone())
+                .collect(),
+            options: positions
+                .iter()
+                .map(|&pos| self.field_options(pos).clone())
+                .collect(),
+        };
+
+        let projector = Projector {
+            version: self.version,
+            num_fields: self.fields.len(),
+            positions,
+        };
+
+        Ok((parts.into(), projector))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{SchemaBuilder, SchemaError, Value, ValueType};
+
+    #[test]
+    fn project_entry() {
+        let schema = SchemaBuilder::new(ValueType::U64)
+            .set_version(2)
+            .add_field("id", ValueType::U64)
+            .add_field("name", ValueType::String)
+            .add_nullable_field("comment", ValueType::String)
+            .add_field("score", ValueType::F64)
+            .build();
+
+        let entry = schema
+            .build_entry()
+            .set_field("id", &1u64)
+            .set_field("name", &"foo")
+            .set_field("score", &2.5)
+            .build();
+
+        let (projected, projector) = schema.project(&["score", "comment", "name"]).unwrap();
+        assert_eq!(projector.len(), 3);
+        assert_eq!(projected.get_version(), 2);
+
+        let partial = projector.project(&entry).unwrap();
+        assert_eq!(partial.num_fields(), 3);
+        assert_eq!(
+            projected.get_fields_as_tuple(&partial).unwrap(),
+            vec![
+                ("score".to_string(), 2.5.into()),
+                ("comment".to_string(), Value::Null),
+                ("name".to_string(), "foo".into()),
+            ]
+        );
+        assert_eq!(
+            projected.get_field(&partial, "id"),
+            Err(SchemaError::NoSuchField("id".to_string()))
+        );
+
+        assert_eq!(
+            schema.project(&["name", "other"]).err(),
+            Some(SchemaError::NoSuchField("other".to_string()))
+        );
+        assert_eq!(
+            schema.project(&["name", "name"]).err(),
+            Some(SchemaError::DuplicateField("name".to_string()))
+        );
+
+        let filtered = schema
+            .get_fields_with_filter(&partial, &["score", "comment", "name"])
+            .unwrap();
+        assert_eq!(filtered.get("name"), Some(&"foo".into()));
+        assert_eq!(
+            schema.get_fields_with_filter(&partial, &["score", "comment", "other"]),
+            Err(SchemaError::NoSuchField("other".to_string()))
+        );
+
+        // Projected entries cannot be projected again
+        assert_eq!(projector.project(&partial), Err(SchemaError::EncodingError));
+    }
+}
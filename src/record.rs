@@ -0,0 +1,209 @@
+//! Encoding of struct fields, i.e., records nested inside an entry
+//!
+//! A struct is stored as a bitmap with one bit set for every null member,
+//! followed by the encoding of every non-null member, in the order they
+//! are declared and using the same element encoding as lists.
+//! Members can be located without decoding the members before them.
+
+use std::borrow::Cow;
+
+use crate::list::{push_element, split_element};
+use crate::{
+    encode_value, FieldTypeList, SchemaError, Value, ValueRef, ValueType, NULLABLE_OPTIONS,
+};
+
+fn bitmap_size(num_members: usize) -> usize {
+    num_members.div_ceil(8)
+}
+
+/// Encodes the members of a struct, given as their encoded value or None if they are null
+/// Returns None if a member is too large to be prefixed with its length.
+pub(crate) fn encode_members(
+    members: &[Option<Cow<[u8]>>],
+    fields: &FieldTypeList,
+) -> Option<Vec<u8>> {
+    let mut buf = vec![0u8; bitmap_size(fields.len())];
+
+    for (pos, (member, (_, mtype))) in members.iter().zip(fields.iter()).enumerate() {
+        match member {
+            Some(bytes) => push_element(bytes, mtype, &mut buf)?,
+            None => buf[pos / 8] |= 1 << (pos % 8),
+        }
+    }
+
+    Some(buf)
+}
+
+/// Encodes the members of a struct value, which are matched to `fields` by name
+/// Missing members are stored as null.
+/// Returns None if a member does not fit into its type.
+pub(crate) fn serialize_struct(
+    members: &[(String, Value)],
+    fields: &FieldTypeList,
+) -> Option<Vec<u8>> {
+    let mut encoded = Vec::with_capacity(fields.len());
+
+    for (fname, ftype) in fields.iter() {
+        let value = members
+            .iter()
+            .find(|(name, _)| name == fname)
+            .map(|(_, value)| value)
+            .filter(|value| !value.is_null());
+
+        match value {
+            Some(value) => encoded.push(Some(Cow::Owned(value.serialize_as(ftype)?))),
+            None => encoded.push(None),
+        }
+    }
+
+    encode_members(&encoded, fields)
+}
+
+/// A struct borrowed from the encoded fields of a `DataEntry`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StructRef<'a> {
+    fields: &'a FieldTypeList,
+    data: &'a [u8],
+}
+
+impl<'a> StructRef<'a> {
+    pub(crate) fn from_bytes(
+        data: &'a [u8],
+        fields: &'a FieldTypeList,
+    ) -> Result<Self, SchemaError> {
+        if data.len() < bitmap_size(fields.len()) {
+            return Err(SchemaError::EncodingError);
+        }
+
+        Ok(Self { fields, data })
+    }
+
+    /// The names and types of all members
+    pub fn field_types(&self) -> &'a FieldTypeList {
+        self.fields
+    }
+
+    fn is_null(&self, pos: usize) -> bool {
+        self.data[pos / 8] & (1 << (pos % 8)) != 0
+    }
+
+    /// The encoded value of every member, or None if it is null
+    pub(crate) fn members(&self) -> Result<Vec<Option<&'a [u8]>>, SchemaError> {
+        let mut data = &self.data[bitmap_size(self.fields.len())..];
+        let mut members = Vec::with_capacity(self.fields.len());
+
+        for (pos, (_, mtype)) in self.fields.iter().enumerate() {
+            if self.is_null(pos) {
+                members.push(None);
+            } else {
+                members.push(Some(split_element(&mut data, mtype)?));
+            }
+        }
+
+        Ok(members)
+    }
+
+    /// Reads the member called `name`, skipping over the members before it
+    pub fn get(&self, name: &str) -> Result<ValueRef<'a>, SchemaError> {
+        let Some(target) = self.fields.iter().position(|(fname, _)| fname == name) else {
+            return Err(SchemaError::NoSuchField(name.to_string()));
+        };
+
+        if self.is_null(target) {
+            return Ok(ValueRef::Null);
+        }
+
+        let mut data = &self.data[bitmap_size(self.fields.len())..];
+
+        for (pos, (_, mtype)) in self.fields.iter().enumerate() {
+            if self.is_null(pos) {
+                continue;
+            }
+
+            let member = split_element(&mut data, mtype)?;
+            if pos == target {
+                return ValueRef::from_bytes(member, mtype);
+            }
+        }
+
+        unreachable!();
+    }
+
+    /// Copies the struct into an owned `Value::Struct`
+    pub fn to_value(&self) -> Result<Value, SchemaError> {
+        let mut members = Vec::with_capacity(self.fields.len());
+
+        for (member, (fname, mtype)) in self.members()?.into_iter().zip(self.fields.iter()) {
+            let value = match member {
+                Some(data) => ValueRef::from_bytes(data, mtype)?.to_value()?,
+                None => Value::Null,
+            };
+
+            members.push((fname.clone(), value));
+        }
+
+        Ok(Value::Struct(members))
+    }
+}
+
+/// Follows a dotted `path` of members, starting at the struct `value`
+/// The path must have been checked against the struct's type.
+pub(crate) fn read_member<'a>(
+    value: ValueRef<'a>,
+    path: &str,
+) -> Result<ValueRef<'a>, SchemaError> {
+    let mut value = value;
+
+    for member in path.split('.') {
+        value = match value {
+            ValueRef::Null => return Ok(ValueRef::Null),
+            ValueRef::Struct(record) => record.get(member)?,
+            _ => return Err(SchemaError::EncodingError),
+        };
+    }
+
+    Ok(value)
+}
+
+/// Re-encodes the struct `data` of type `vtype` with the member at `path` set to `value`
+/// The other members are copied without decoding them. If the struct is null,
+/// all other members are null.
+pub(crate) fn update_member(
+    name: &str,
+    vtype: &ValueType,
+    data: Option<&[u8]>,
+    path: &str,
+    value: &Value,
+) -> Result<Vec<u8>, SchemaError> {
+    let ValueType::Struct(fields) = vtype else {
+        return Err(SchemaError::NoSuchField(name.to_string()));
+    };
+
+    let members = match data {
+        Some(data) => StructRef::from_bytes(data, fields)?.members()?,
+        None => vec![None; fields.len()],
+    };
+
+    let (member, rest) = match path.split_once('.') {
+        Some((member, rest)) => (member, Some(rest)),
+        None => (path, None),
+    };
+
+    let Some(pos) = fields.iter().position(|(fname, _)| fname == member) else {
+        return Err(SchemaError::NoSuchField(name.to_string()));
+    };
+    let mtype = &fields[pos].1;
+
+    let encoded = match rest {
+        Some(rest) => Some(update_member(name, mtype, members[pos], rest, value)?),
+        None => encode_value(name, mtype, value, &NULLABLE_OPTIONS)?,
+    };
+
+    let mut members: Vec<_> = members
+        .into_iter()
+        .map(|member| member.map(Cow::Borrowed))
+        .collect();
+    members[pos] = encoded.map(Cow::Owned);
+
+    encode_members(&members, fields).ok_or(SchemaError::EntryTooLarge)
+}
@@ -0,0 +1,816 @@
+//! Conversion between entries and types that implement `Serialize` or `Deserialize`
+//!
+//! Values are first converted to a `Value`, and then stored just like values
+//! passed to `EntryBuilder::set_field_from_value`, so they are checked against
+//! the type of their field. Narrow numbers, options, sequences, maps and
+//! nested structs map to the corresponding field types, and enums without data
+//! are stored as the name of their variant. Fields holding dates, times,
+//! decimals or UUIDs are exchanged as strings, or in the serialized form of
+//! the crate's own `Timestamp`, `Date`, `Time`, `Duration`, `Decimal` and
+//! `Uuid` types when those are used.
+
+use std::fmt::Display;
+
+use serde::de::value::{MapDeserializer, SeqDeserializer};
+use serde::de::{self, DeserializeOwned, DeserializeSeed, IntoDeserializer, MapAccess, Visitor};
+use serde::ser::{self, Impossible, Serialize};
+
+use crate::{
+    DataEntry, Date, Decimal, Duration, Schema, SchemaError, Time, Timestamp, Tuple, Uuid, Value,
+    ValueType,
+};
+
+impl ser::Error for SchemaError {
+    fn custom<T: Display>(msg: T) -> Self {
+        SchemaError::Serde(msg.to_string())
+    }
+}
+
+impl de::Error for SchemaError {
+    fn custom<T: Display>(msg: T) -> Self {
+        SchemaError::Serde(msg.to_string())
+    }
+}
+
+fn unsupported(what: &str) -> SchemaError {
+    SchemaError::Serde(format!("{} are not supported", what))
+}
+
+/// Converts a serialized value to the representation of `vtype`, where serde's
+/// data model does not have a direct equivalent
+fn coerce(value: Value, vtype: &ValueType) -> Result<Value, SchemaError> {
+    let value = match (value, vtype) {
+        // Byte vectors are serialized as sequences, unless they use serde_bytes
+        (Value::List(items), ValueType::Bytes) => {
+            let bytes: Option<Vec<u8>> = items
+                .iter()
+                .map(|item| match item {
+                    Value::U64(u) => u8::try_from(*u).ok(),
+                    _ => None,
+                })
+                .collect();
+
+            match bytes {
+                Some(bytes) => Value::Bytes(bytes),
+                None => Value::List(items),
+            }
+        }
+        (Value::String(s), ValueType::Timestamp { .. }) => Value::Timestamp(Timestamp::parse(&s)?),
+        (Value::String(s), ValueType::Date) => Value::Date(Date::parse(&s)?),
+        (Value::String(s), ValueType::Time) => Value::Time(Time::parse(&s)?),
+        (Value::String(s), ValueType::Duration) => Value::Duration(Duration::parse(&s)?),
+        (Value::String(s), ValueType::Decimal { .. }) => Value::Decimal(Decimal::parse(&s)?),
+        (Value::String(s), ValueType::Uuid) => Value::Uuid(Uuid::parse(&s)?),
+        (value, ValueType::Timestamp { .. }) => from_derived(value, Value::Timestamp),
+        (value, ValueType::Date) => from_derived(value, Value::Date),
+        (value, ValueType::Time) => from_derived(value, Value::Time),
+        (value, ValueType::Duration) => from_derived(value, Value::Duration),
+        (value, ValueType::Decimal { .. }) => from_derived(value, Value::Decimal),
+        (value, ValueType::Uuid) => from_derived(value, Value::Uuid),
+        (Value::List(items), ValueType::List(elem)) => Value::List(
+            items
+                .into_iter()
+                .map(|item| coerce(item, elem))
+                .collect::<Result<_, _>>()?,
+        ),
+        (Value::Map(entries), ValueType::Map(key_type, value_type)) => Value::Map(
+            entries
+                .into_iter()
+                .map(|(key, value)| Ok((coerce(key, key_type)?, coerce(value, value_type)?)))
+                .collect::<Result<_, SchemaError>>()?,
+        ),
+        (Value::Struct(members), ValueType::Struct(fields)) => {
+            Value::Struct(coerce_members(members, fields)?)
+        }
+        (value, _) => value,
+    };
+
+    Ok(value)
+}
+
+/// Restores one of the crate's own types from the form its `Serialize` impl produces
+/// Other values are kept, so that they are reported as a type mismatch.
+fn from_derived<T: DeserializeOwned>(value: Value, wrap: fn(T) -> Value) -> Value {
+    match T::deserialize(ValueDeserializer(value.clone())) {
+        Ok(restored) => wrap(restored),
+        Err(_) => value,
+    }
+}
+
+/// The serialized form of a temporal, decimal or UUID value, if `name` is the
+/// name of its type, so that it can be deserialized into the crate's own types
+fn derived_form(value: &Value, name: &str) -> Option<Value> {
+    let derived = match (value, name) {
+        (Value::Timestamp(ts), "Timestamp") => ts.serialize(ValueSerializer),
+        (Value::Date(date), "Date") => date.serialize(ValueSerializer),
+        (Value::Time(time), "Time") => time.serialize(ValueSerializer),
+        (Value::Duration(duration), "Duration") => duration.serialize(ValueSerializer),
+        (Value::Decimal(decimal), "Decimal") => decimal.serialize(ValueSerializer),
+        (Value::Uuid(uuid), "Uuid") => uuid.serialize(ValueSerializer),
+        _ => return None,
+    };
+
+    derived.ok()
+}
+
+/// Coerces every member that has a matching field
+/// Other members are kept, so that they are reported as unknown.
+fn coerce_members(members: Tuple, fields: &[(String, ValueType)]) -> Result<Tuple, SchemaError> {
+    members
+        .into_iter()
+        .map(
+            |(name, value)| match fields.iter().find(|(fname, _)| fname == &name) {
+                Some((_, ftype)) => Ok((name, coerce(value, ftype)?)),
+                None => Ok((name, value)),
+            },
+        )
+        .collect()
+}
+
+impl Schema {
+    /// Stores a value as an entry of this schema
+    ///
+    /// The value must serialize as a struct, or as a map with string keys.
+    /// Its fields are matched to the fields of the schema by name, and
+    /// errors are reported the same way as by `EntryBuilder::try_build`.
+    pub fn serialize_entry<T: Serialize + ?Sized>(
+        &self,
+        value: &T,
+    ) -> Result<DataEntry, SchemaError> {
+        let members = match value.serialize(ValueSerializer)? {
+            Value::Struct(members) => members,
+            Value::Map(entries) => entries
+                .into_iter()
+                .map(|(key, value)| match key {
+                    Value::String(name) => Ok((name, value)),
+                    _ => Err(SchemaError::Serde("map keys must be strings".to_string())),
+                })
+                .collect::<Result<_, _>>()?,
+            _ => {
+                return Err(SchemaError::Serde(
+                    "entries must be serialized from a struct or map".to_string(),
+                ))
+            }
+        };
+
+        let members = coerce_members(members, &self.fields)?;
+        let mut builder = self.build_entry();
+
+        for (name, value) in members.iter() {
+            builder = builder.set_field_from_value(name, value);
+        }
+
+        builder.try_build()
+    }
+
+    /// Restores a value from an entry of this schema
+    /// The fields of the entry are passed to `T` as a struct.
+    pub fn deserialize_entry<T: DeserializeOwned>(
+        &self,
+        entry: &DataEntry,
+    ) -> Result<T, SchemaError> {
+        let fields = self.get_fields_as_tuple(entry)?;
+        T::deserialize(ValueDeserializer(Value::Struct(fields)))
+    }
+}
+
+/// Serializes any value into a `Value`
+struct ValueSerializer;
+
+struct SeqSerializer {
+    items: Vec<Value>,
+}
+
+struct MapSerializer {
+    entries: Vec<(Value, Value)>,
+    next_key: Option<Value>,
+}
+
+struct StructSerializer {
+    members: Tuple,
+}
+
+impl ser::Serializer for ValueSerializer {
+    type Ok = Value;
+    type Error = SchemaError;
+
+    type SerializeSeq = SeqSerializer;
+    type SerializeTuple = SeqSerializer;
+    type SerializeTupleStruct = SeqSerializer;
+    type SerializeTupleVariant = Impossible<Value, SchemaError>;
+    type SerializeMap = MapSerializer;
+    type SerializeStruct = StructSerializer;
+    type SerializeStructVariant = Impossible<Value, SchemaError>;
+
+    fn serialize_bool(self, v: bool) -> Result<Value, SchemaError> {
+        Ok(Value::Bool(v))
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<Value, SchemaError> {
+        Ok(Value::I64(v as i64))
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<Value, SchemaError> {
+        Ok(Value::I64(v as i64))
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<Value, SchemaError> {
+        Ok(Value::I64(v as i64))
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<Value, SchemaError> {
+        Ok(Value::I64(v))
+    }
+
+    fn serialize_i128(self, v: i128) -> Result<Value, SchemaError> {
+        Ok(Value::I128(v))
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<Value, SchemaError> {
+        Ok(Value::U64(v as u64))
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<Value, SchemaError> {
+        Ok(Value::U64(v as u64))
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<Value, SchemaError> {
+        Ok(Value::U64(v as u64))
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<Value, SchemaError> {
+        Ok(Value::U64(v))
+    }
+
+    fn serialize_u128(self, v: u128) -> Result<Value, SchemaError> {
+        Ok(Value::U128(v))
+    }
+
+    fn serialize_f32(self, v: f32) -> Result<Value, SchemaError> {
+        Ok(Value::F64(v as f64))
+    }
+
+    fn serialize_f64(self, v: f64) -> Result<Value, SchemaError> {
+        Ok(Value::F64(v))
+    }
+
+    fn serialize_char(self, v: char) -> Result<Value, SchemaError> {
+        Ok(Value::String(v.to_string()))
+    }
+
+    fn serialize_str(self, v: &str) -> Result<Value, SchemaError> {
+        Ok(Value::String(v.to_string()))
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> Result<Value, SchemaError> {
+        Ok(Value::Bytes(v.to_vec()))
+    }
+
+    fn serialize_none(self) -> Result<Value, SchemaError> {
+        Ok(Value::Null)
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<Value, SchemaError> {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<Value, SchemaError> {
+        Ok(Value::Null)
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<Value, SchemaError> {
+        Ok(Value::Null)
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+    ) -> Result<Value, SchemaError> {
+        Ok(Value::String(variant.to_string()))
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<Value, SchemaError> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        _index: u32,
+        _variant: &'static str,
+        _value: &T,
+    ) -> Result<Value, SchemaError> {
+        Err(unsupported("enum variants with data"))
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<SeqSerializer, SchemaError> {
+        Ok(SeqSerializer {
+            items: Vec::with_capacity(len.unwrap_or(0)),
+        })
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<SeqSerializer, SchemaError> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        len: usize,
+    ) -> Result<SeqSerializer, SchemaError> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleVariant, SchemaError> {
+        Err(unsupported("enum variants with data"))
+    }
+
+    fn serialize_map(self, len: Option<usize>) -> Result<MapSerializer, SchemaError> {
+        Ok(MapSerializer {
+            entries: Vec::with_capacity(len.unwrap_or(0)),
+            next_key: None,
+        })
+    }
+
+    fn serialize_struct(
+        self,
+        _name: &'static str,
+        len: usize,
+    ) -> Result<StructSerializer, SchemaError> {
+        Ok(StructSerializer {
+            members: Vec::with_capacity(len),
+        })
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStructVariant, SchemaError> {
+        Err(unsupported("enum variants with data"))
+    }
+}
+
+impl ser::SerializeSeq for SeqSerializer {
+    type Ok = Value;
+    type Error = SchemaError;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), SchemaError> {
+        self.items.push(value.serialize(ValueSerializer)?);
+        Ok(())
+    }
+
+    fn end(self) -> Result<Value, SchemaError> {
+        Ok(Value::List(self.items))
+    }
+}
+
+impl ser::SerializeTuple for SeqSerializer {
+    type Ok = Value;
+    type Error = SchemaError;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), SchemaError> {
+        ser::SerializeSeq::serialize_element(self, value)
+    }
+
+    fn end(self) -> Result<Value, SchemaError> {
+        ser::SerializeSeq::end(self)
+    }
+}
+
+impl ser::SerializeTupleStruct for SeqSerializer {
+    type Ok = Value;
+    type Error = SchemaError;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), SchemaError> {
+        ser::SerializeSeq::serialize_element(self, value)
+    }
+
+    fn end(self) -> Result<Value, SchemaError> {
+        ser::SerializeSeq::end(self)
+    }
+}
+
+impl ser::SerializeMap for MapSerializer {
+    type Ok = Value;
+    type Error = SchemaError;
+
+    fn serialize_key<T: Serialize + ?Sized>(&mut self, key: &T) -> Result<(), SchemaError> {
+        self.next_key = Some(key.serialize(ValueSerializer)?);
+        Ok(())
+    }
+
+    fn serialize_value<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), SchemaError> {
+        let Some(key) = self.next_key.take() else {
+            return Err(SchemaError::Serde("map value without a key".to_string()));
+        };
+
+        self.entries.push((key, value.serialize(ValueSerializer)?));
+        Ok(())
+    }
+
+    fn end(self) -> Result<Value, SchemaError> {
+        Ok(Value::Map(self.entries))
+    }
+}
+
+impl ser::SerializeStruct for StructSerializer {
+    type Ok = Value;
+    type Error = SchemaError;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), SchemaError> {
+        self.members
+            .push((key.to_string(), value.serialize(ValueSerializer)?));
+        Ok(())
+    }
+
+    fn end(self) -> Result<Value, SchemaError> {
+        Ok(Value::Struct(self.members))
+    }
+}
+
+/// Deserializes any value from a `Value`
+pub struct ValueDeserializer(Value);
+
+impl<'de> IntoDeserializer<'de, SchemaError> for Value {
+    type Deserializer = ValueDeserializer;
+
+    fn into_deserializer(self) -> ValueDeserializer {
+        ValueDeserializer(self)
+    }
+}
+
+/// The members of a struct, which adds the name of a member to its errors
+struct StructAccess {
+    members: std::vec::IntoIter<(String, Value)>,
+    next: Option<(String, Value)>,
+}
+
+impl<'de> MapAccess<'de> for StructAccess {
+    type Error = SchemaError;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, SchemaError> {
+        let Some((name, value)) = self.members.next() else {
+            return Ok(None);
+        };
+
+        let key = seed.deserialize(name.clone().into_deserializer())?;
+        self.next = Some((name, value));
+        Ok(Some(key))
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(
+        &mut self,
+        seed: V,
+    ) -> Result<V::Value, SchemaError> {
+        let Some((name, value)) = self.next.take() else {
+            return Err(SchemaError::Serde(
+                "struct member without a name".to_string(),
+            ));
+        };
+
+        seed.deserialize(ValueDeserializer(value))
+            .map_err(|err| match err {
+                SchemaError::Serde(msg) => SchemaError::Serde(format!("{}: {}", name, msg)),
+                err => err,
+            })
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.members.len())
+    }
+}
+
+impl<'de> de::Deserializer<'de> for ValueDeserializer {
+    type Error = SchemaError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, SchemaError> {
+        match self.0 {
+            Value::Null => visitor.visit_unit(),
+            Value::String(s) => visitor.visit_string(s),
+            Value::Bytes(b) => visitor.visit_byte_buf(b),
+            Value::F64(f) => visitor.visit_f64(f),
+            Value::I64(i) => visitor.visit_i64(i),
+            Value::U64(u) => visitor.visit_u64(u),
+            Value::I128(i) => visitor.visit_i128(i),
+            Value::U128(u) => visitor.visit_u128(u),
+            Value::Bool(b) => visitor.visit_bool(b),
+            Value::Timestamp(ts) => visitor.visit_string(ts.to_string()),
+            Value::Date(date) => visitor.visit_string(date.to_string()),
+            Value::Time(time) => visitor.visit_string(time.to_string()),
+            Value::Duration(duration) => visitor.visit_string(duration.to_string()),
+            Value::Decimal(decimal) => visitor.visit_string(decimal.to_string()),
+            Value::Uuid(uuid) => visitor.visit_string(uuid.to_string()),
+            Value::List(items) => {
+                let mut seq = SeqDeserializer::new(items.into_iter());
+                let value = visitor.visit_seq(&mut seq)?;
+                seq.end()?;
+                Ok(value)
+            }
+            Value::Map(entries) => {
+                let mut map = MapDeserializer::new(entries.into_iter());
+                let value = visitor.visit_map(&mut map)?;
+                map.end()?;
+                Ok(value)
+            }
+            Value::Struct(members) => visitor.visit_map(StructAccess {
+                members: members.into_iter(),
+                next: None,
+            }),
+            #[cfg(feature = "json")]
+            Value::Json(v) => {
+                de::Deserializer::deserialize_any(*v, visitor).map_err(de::Error::custom)
+            }
+        }
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, SchemaError> {
+        if self.0.is_null() {
+            visitor.visit_none()
+        } else {
+            visitor.visit_some(self)
+        }
+    }
+
+    fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, SchemaError> {
+        // Allows reading bytes into a Vec<u8> without serde_bytes
+        if let Value::Bytes(bytes) = self.0 {
+            let mut seq = SeqDeserializer::new(bytes.into_iter());
+            let value = visitor.visit_seq(&mut seq)?;
+            seq.end()?;
+            Ok(value)
+        } else {
+            self.deserialize_any(visitor)
+        }
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, SchemaError> {
+        match derived_form(&self.0, name) {
+            Some(value) => visitor.visit_newtype_struct(ValueDeserializer(value)),
+            None => visitor.visit_newtype_struct(self),
+        }
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, SchemaError> {
+        match derived_form(&self.0, name) {
+            Some(value) => ValueDeserializer(value).deserialize_any(visitor),
+            None => self.deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, SchemaError> {
+        match self.0 {
+            Value::String(variant) => visitor.visit_enum(variant.into_deserializer()),
+            _ => Err(unsupported("enum variants with data")),
+        }
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf unit unit_struct tuple tuple_struct map identifier
+        ignored_any
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use crate::{
+        Date, Decimal, Duration, Schema, SchemaBuilder, SchemaError, Time, TimeUnit, Timestamp,
+        Uuid, Value, ValueType,
+    };
+
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    enum Status {
+        Active,
+        Closed,
+    }
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct Address {
+        city: String,
+        zip: Option<u32>,
+    }
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct Account {
+        name: String,
+        balance: i32,
+        status: Status,
+        tags: Vec<String>,
+        avatar: Vec<u8>,
+        address: Address,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        comment: Option<String>,
+    }
+
+    fn schema() -> Schema {
+        SchemaBuilder::new(ValueType::U64)
+            .add_field("name", ValueType::String)
+            .add_field("balance", ValueType::I32)
+            .add_field(
+                "status",
+                ValueType::Enum(vec!["Active".to_string(), "Closed".to_string()]),
+            )
+            .add_field("tags", ValueType::List(Box::new(ValueType::String)))
+            .add_field("avatar", ValueType::Bytes)
+            .add_field(
+                "address",
+                ValueType::Struct(vec![
+                    ("city".to_string(), ValueType::String),
+                    ("zip".to_string(), ValueType::U32),
+                ]),
+            )
+            .add_nullable_field("comment", ValueType::String)
+            .build()
+    }
+
+    #[test]
+    fn round_trip() {
+        let schema = schema();
+        let account = Account {
+            name: "alice".to_string(),
+            balance: -5,
+            status: Status::Closed,
+            tags: vec!["a".to_string(), "b".to_string()],
+            avatar: vec![1, 2, 3],
+            address: Address {
+                city: "Ithaca".to_string(),
+                zip: None,
+            },
+            comment: None,
+        };
+
+        let entry = schema.serialize_entry(&account).unwrap();
+        assert_eq!(schema.get_field(&entry, "status").unwrap(), "Closed".into());
+        assert_eq!(
+            schema.get_field(&entry, "avatar").unwrap(),
+            Value::Bytes(vec![1, 2, 3])
+        );
+        assert_eq!(schema.get_field(&entry, "comment").unwrap(), Value::Null);
+
+        let account2: Account = schema.deserialize_entry(&entry).unwrap();
+        assert_eq!(account, account2);
+    }
+
+    #[test]
+    fn crate_types() {
+        #[derive(Serialize, Deserialize, Debug, PartialEq)]
+        struct Event {
+            id: Uuid,
+            at: Timestamp,
+            day: Date,
+            time: Time,
+            length: Duration,
+            price: Decimal,
+            label: String,
+        }
+
+        #[derive(Deserialize)]
+        struct EventText {
+            id: String,
+            day: String,
+            price: String,
+        }
+
+        let schema = SchemaBuilder::new(ValueType::U64)
+            .add_field("id", ValueType::Uuid)
+            .add_field(
+                "at",
+                ValueType::Timestamp {
+                    unit: TimeUnit::Milliseconds,
+                    timezone: None,
+                },
+            )
+            .add_field("day", ValueType::Date)
+            .add_field("time", ValueType::Time)
+            .add_field("length", ValueType::Duration)
+            .add_field(
+                "price",
+                ValueType::Decimal {
+                    precision: 10,
+                    scale: 2,
+                },
+            )
+            .add_field("label", ValueType::Date)
+            .build();
+
+        let event = Event {
+            id: Uuid::from_u128(0x1234_5678),
+            at: Timestamp::new(1_700_000_000_123, TimeUnit::Milliseconds),
+            day: Date::from_ymd(2024, 2, 29).unwrap(),
+            time: Time::from_hms_nano(12, 30, 0, 0).unwrap(),
+            length: Duration::from_secs(90).unwrap(),
+            price: Decimal::new(1999, 2).unwrap(),
+            label: "2024-03-01".to_string(),
+        };
+
+        let entry = schema.serialize_entry(&event).unwrap();
+        assert_eq!(schema.get_field(&entry, "id").unwrap(), event.id.into());
+        assert_eq!(
+            schema.get_field(&entry, "price").unwrap(),
+            event.price.into()
+        );
+
+        let event2: Event = schema.deserialize_entry(&entry).unwrap();
+        assert_eq!(event, event2);
+
+        // Other types still read these fields as strings
+        let text: EventText = schema.deserialize_entry(&entry).unwrap();
+        assert_eq!(text.id, event.id.to_string());
+        assert_eq!(text.day, "2024-02-29");
+        assert_eq!(text.price, "19.99");
+    }
+
+    #[test]
+    fn type_errors() {
+        #[derive(Serialize, Deserialize)]
+        struct Wrong {
+            name: u64,
+        }
+
+        let schema = SchemaBuilder::new(ValueType::U64)
+            .add_field("name", ValueType::String)
+            .build();
+
+        let Err(SchemaError::InvalidEntry(err)) = schema.serialize_entry(&Wrong { name: 1 }) else {
+            panic!("Expected an invalid entry");
+        };
+        assert_eq!(
+            err.invalid_values,
+            vec![SchemaError::TypeMismatch {
+                field: "name".to_string(),
+                expected: ValueType::String,
+                got: ValueType::U64,
+            }]
+        );
+
+        let entry = schema.build_entry().set_field("name", &"foo").build();
+        let result = schema.deserialize_entry::<Wrong>(&entry);
+        assert!(matches!(result, Err(SchemaError::Serde(msg)) if msg.starts_with("name: ")));
+    }
+
+    #[test]
+    fn unknown_members() {
+        #[derive(Serialize)]
+        struct Extra {
+            name: String,
+            lookup: BTreeMap<Vec<u32>, bool>,
+        }
+
+        let schema = SchemaBuilder::new(ValueType::U64)
+            .add_field("name", ValueType::String)
+            .build();
+
+        // Lists cannot be map keys, but unknown members are never encoded
+        let value = Extra {
+            name: "foo".to_string(),
+            lookup: BTreeMap::from([(vec![1], true), (vec![2, 3], false)]),
+        };
+
+        let Err(SchemaError::InvalidEntry(err)) = schema.serialize_entry(&value) else {
+            panic!("Expected an invalid entry");
+        };
+        assert_eq!(err.unknown_fields, vec!["lookup".to_string()]);
+        assert!(err.missing_fields.is_empty());
+        assert!(err.invalid_values.is_empty());
+    }
+}
@@ -0,0 +1,81 @@
+//! Rust types whose values are stored as entries of a schema
+//!
+//! The `Record` trait can be implemented by hand, or derived using
+//! `#[derive(Record)]` if the `derive` feature is enabled.
+//! The derive macro maps every field of a struct to a field of the schema,
+//! and accepts the following attributes:
+//!
+//! * `#[schema(key)]` uses the field's type as the key type of the schema.
+//!   Exactly one field must be marked as the key. It is stored in the entry
+//!   as well, so that `from_entry` can restore it.
+//! * `#[schema(rename = "name")]` stores the field under a different name.
+//! * `#[schema(default = expr)]` sets the default value of the field.
+//! * `#[schema(nullable)]` marks a field as nullable. Fields of type
+//!   `Option<T>` are always nullable, and nullable fields must have that type.
+//!
+//! Fields must be of a type that implements `HasValueType`.
+//!
+//! The generated code refers to this crate as `::schema`. If the dependency
+//! is renamed, pass its path with `#[schema(crate = "path")]` on the struct.
+
+use crate::{DataEntry, HasValueType, Schema, SchemaError, Value};
+
+pub trait Record: Sized {
+    /// The schema that values of this type are stored with
+    fn try_schema() -> Result<Schema, SchemaError>;
+
+    /// Same as `try_schema`, but panics if the schema is invalid
+    fn schema() -> Schema {
+        match Self::try_schema() {
+            Ok(schema) => schema,
+            Err(err) => panic!("{}", err),
+        }
+    }
+
+    /// The key of this value, as it would be passed to `key::encode_key`
+    fn key(&self) -> Value;
+
+    /// Stores this value as an entry of `Self::schema()`
+    fn try_to_entry(&self) -> Result<DataEntry, SchemaError>;
+
+    /// Same as `try_to_entry`, but panics if the value cannot be stored
+    fn to_entry(&self) -> DataEntry {
+        match self.try_to_entry() {
+            Ok(entry) => entry,
+            Err(err) => panic!("{}", err),
+        }
+    }
+
+    /// Restores a value from an entry written with `schema`
+    fn from_entry(schema: &Schema, entry: &DataEntry) -> Result<Self, SchemaError>;
+}
+
+/// Converts the value read from the field `name` to `T`
+pub fn value_into<T>(name: &str, value: Value) -> Result<T, SchemaError>
+where
+    T: HasValueType,
+    Value: TryInto<T>,
+{
+    let Some(got) = value.get_type() else {
+        return Err(SchemaError::NotNullable(name.to_string()));
+    };
+
+    value.try_into().map_err(|_| SchemaError::TypeMismatch {
+        field: name.to_string(),
+        expected: T::value_type(),
+        got,
+    })
+}
+
+/// Same as value_into, but converts `Value::Null` to None
+pub fn nullable_value_into<T>(name: &str, value: Value) -> Result<Option<T>, SchemaError>
+where
+    T: HasValueType,
+    Value: TryInto<T>,
+{
+    if value.is_null() {
+        Ok(None)
+    } else {
+        value_into(name, value).map(Some)
+    }
+}
@@ -0,0 +1,95 @@
+//! Universally unique identifiers
+
+use serde::{Deserialize, Serialize};
+
+use crate::SchemaError;
+
+/// A 128-bit UUID, stored as its 16 bytes in big-endian order
+///
+/// UUIDs are ordered by their bytes, which matches the order of their
+/// textual representation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct Uuid([u8; 16]);
+
+impl Uuid {
+    pub fn from_bytes(bytes: [u8; 16]) -> Self {
+        Self(bytes)
+    }
+
+    pub fn as_bytes(&self) -> &[u8; 16] {
+        &self.0
+    }
+
+    pub fn from_u128(value: u128) -> Self {
+        Self(value.to_be_bytes())
+    }
+
+    pub fn as_u128(&self) -> u128 {
+        u128::from_be_bytes(self.0)
+    }
+
+    /// Parses a UUID in its hyphenated form, e.g., `67e55044-10b1-426f-9247-bb680e5fe0c8`,
+    /// or as 32 hexadecimal digits without hyphens
+    pub fn parse(input: &str) -> Result<Self, SchemaError> {
+        let error = || SchemaError::ParseError(input.to_string());
+
+        let digits: String = match input.len() {
+            32 => input.to_string(),
+            36 => {
+                let groups: Vec<&str> = input.split('-').collect();
+                let lengths: Vec<usize> = groups.iter().map(|g| g.len()).collect();
+
+                if lengths != [8, 4, 4, 4, 12] {
+                    return Err(error());
+                }
+
+                groups.concat()
+            }
+            _ => return Err(error()),
+        };
+
+        if !digits.bytes().all(|c| c.is_ascii_hexdigit()) {
+            return Err(error());
+        }
+
+        u128::from_str_radix(&digits, 16)
+            .map(Self::from_u128)
+            .map_err(|_| error())
+    }
+}
+
+impl std::fmt::Display for Uuid {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let hex = format!("{:032x}", self.as_u128());
+
+        write!(
+            fmt,
+            "{}-{}-{}-{}-{}",
+            &hex[..8],
+            &hex[8..12],
+            &hex[12..16],
+            &hex[16..20],
+            &hex[20..]
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Uuid;
+
+    #[test]
+    fn parse_and_format() {
+        let s = "67e55044-10b1-426f-9247-bb680e5fe0c8";
+        let uuid = Uuid::parse(s).unwrap();
+
+        assert_eq!(uuid.as_bytes()[0], 0x67);
+        assert_eq!(uuid.to_string(), s);
+        assert_eq!(Uuid::parse(&s.to_uppercase()).unwrap(), uuid);
+        assert_eq!(Uuid::parse(&s.replace('-', "")).unwrap(), uuid);
+
+        assert!(Uuid::parse("67e55044-10b1-426f-9247-bb680e5fe0c").is_err());
+        assert!(Uuid::parse("67e5504410b1-426f-9247-bb680e5fe0c8-").is_err());
+        assert!(Uuid::parse("+7e55044-10b1-426f-9247-bb680e5fe0c8").is_err());
+    }
+}
@@ -3,6 +3,14 @@ use std::convert::TryInto;
 
 use serde::{Deserialize, Serialize};
 
+use crate::datetime::{Date, Duration, Time, TimeUnit, Timestamp};
+use crate::decimal::Decimal;
+use crate::list::{serialize_list, ListRef};
+use crate::map::{serialize_map, MapRef};
+use crate::record::{serialize_struct, StructRef};
+use crate::uuid::Uuid;
+use crate::{FieldTypeList, SchemaError, Tuple};
+
 #[cfg(feature = "python-bindings")]
 use pyo3::prelude::*;
 
@@ -17,26 +25,296 @@ use pyo3::types::*;
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub enum Value {
+    Null,
     String(String),
+    Bytes(Vec<u8>),
     F64(f64),
     I64(i64),
     U64(u64),
+    I128(i128),
+    U128(u128),
     Bool(bool),
+    Timestamp(Timestamp),
+    Date(Date),
+    Time(Time),
+    Duration(Duration),
+    Decimal(Decimal),
+    Uuid(Uuid),
+    List(Vec<Value>),
+    /// The entries of a map
+    /// Maps read from an entry are ordered by key.
+    Map(Vec<(Value, Value)>),
+    /// The members of a struct, by name
+    Struct(Tuple),
     #[cfg(feature = "json")]
     Json(Box<serde_json::Value>),
 }
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+/// The type of a field
+///
+/// Fields of the narrow numeric types, such as `I32` or `F32`, use less space
+/// but are read as `Value::I64`, `Value::U64` or `Value::F64`.
+/// Writing a value that does not fit into such a field fails.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub enum ValueType {
     String,
+    Bytes,
     F64,
     I64,
     U64,
+    I128,
+    U128,
+    I8,
+    I16,
+    I32,
+    U8,
+    U16,
+    U32,
+    F32,
     Bool,
+    /// A point in time, stored in `unit`s since the Unix epoch
+    /// The timezone, e.g. `UTC` or `+02:00`, only affects how values are formatted.
+    Timestamp {
+        unit: TimeUnit,
+        timezone: Option<String>,
+    },
+    Date,
+    Time,
+    Duration,
+    /// A fixed-point number with at most `precision` digits, `scale` of
+    /// which are after the decimal point
+    Decimal {
+        precision: u8,
+        scale: u8,
+    },
+    Uuid,
+    /// A list whose elements all have the given type and are never null
+    List(Box<ValueType>),
+    /// A map from keys of the first type to values of the second type
+    /// Keys must be of a type that can be used as a key, and values are never null.
+    Map(Box<ValueType>, Box<ValueType>),
+    /// A record nested inside the entry, whose members are all nullable
+    Struct(FieldTypeList),
+    /// A string that is one of the given labels
+    /// Values are stored as the index of their label, and read as `Value::String`.
+    Enum(Vec<String>),
     #[cfg(feature = "json")]
     Json,
 }
 
+/// Maps a Rust type to the ValueType it is stored as
+///
+/// This allows the generic write paths, such as `EntryBuilder::set_field`,
+/// to decode their serialized arguments. They are then checked and converted
+/// just like the `Value` they convert into, so narrow integers can be written
+/// to wide fields.
+pub trait HasValueType {
+    fn value_type() -> ValueType;
+}
+
+impl<T: HasValueType + ?Sized> HasValueType for &T {
+    fn value_type() -> ValueType {
+        T::value_type()
+    }
+}
+
+impl HasValueType for str {
+    fn value_type() -> ValueType {
+        ValueType::String
+    }
+}
+
+impl HasValueType for String {
+    fn value_type() -> ValueType {
+        ValueType::String
+    }
+}
+
+impl HasValueType for [u8] {
+    fn value_type() -> ValueType {
+        ValueType::Bytes
+    }
+}
+
+impl HasValueType for Vec<u8> {
+    fn value_type() -> ValueType {
+        ValueType::Bytes
+    }
+}
+
+impl HasValueType for f64 {
+    fn value_type() -> ValueType {
+        ValueType::F64
+    }
+}
+
+impl HasValueType for i64 {
+    fn value_type() -> ValueType {
+        ValueType::I64
+    }
+}
+
+impl HasValueType for u64 {
+    fn value_type() -> ValueType {
+        ValueType::U64
+    }
+}
+
+impl HasValueType for i128 {
+    fn value_type() -> ValueType {
+        ValueType::I128
+    }
+}
+
+impl HasValueType for u128 {
+    fn value_type() -> ValueType {
+        ValueType::U128
+    }
+}
+
+impl HasValueType for i8 {
+    fn value_type() -> ValueType {
+        ValueType::I8
+    }
+}
+
+impl HasValueType for i16 {
+    fn value_type() -> ValueType {
+        ValueType::I16
+    }
+}
+
+impl HasValueType for i32 {
+    fn value_type() -> ValueType {
+        ValueType::I32
+    }
+}
+
+impl HasValueType for u8 {
+    fn value_type() -> ValueType {
+        ValueType::U8
+    }
+}
+
+impl HasValueType for u16 {
+    fn value_type() -> ValueType {
+        ValueType::U16
+    }
+}
+
+impl HasValueType for u32 {
+    fn value_type() -> ValueType {
+        ValueType::U32
+    }
+}
+
+impl HasValueType for f32 {
+    fn value_type() -> ValueType {
+        ValueType::F32
+    }
+}
+
+impl HasValueType for bool {
+    fn value_type() -> ValueType {
+        ValueType::Bool
+    }
+}
+
+impl HasValueType for Date {
+    fn value_type() -> ValueType {
+        ValueType::Date
+    }
+}
+
+impl HasValueType for Time {
+    fn value_type() -> ValueType {
+        ValueType::Time
+    }
+}
+
+impl HasValueType for Duration {
+    fn value_type() -> ValueType {
+        ValueType::Duration
+    }
+}
+
+impl HasValueType for Uuid {
+    fn value_type() -> ValueType {
+        ValueType::Uuid
+    }
+}
+
+impl ValueType {
+    /// The type of the values read from a field of this type
+    pub fn widened(&self) -> ValueType {
+        match self {
+            Self::I8 | Self::I16 | Self::I32 => Self::I64,
+            Self::U8 | Self::U16 | Self::U32 => Self::U64,
+            Self::F32 => Self::F64,
+            _ => self.clone(),
+        }
+    }
+
+    /// The size of every encoded value of this type, or None if it varies
+    pub fn fixed_size(&self) -> Option<usize> {
+        let size = match self {
+            Self::I8 | Self::U8 | Self::Bool => 1,
+            Self::I16 | Self::U16 => 2,
+            Self::I32 | Self::U32 | Self::F32 | Self::Date => 4,
+            Self::I64 | Self::U64 | Self::F64 => 8,
+            Self::Timestamp { .. } | Self::Time | Self::Duration => 8,
+            Self::I128 | Self::U128 | Self::Decimal { .. } | Self::Uuid => 16,
+            _ => return None,
+        };
+
+        Some(size)
+    }
+
+    /// Can values of this type be converted to `target` using `Value::widen`?
+    pub fn can_widen_to(&self, target: &ValueType) -> bool {
+        match (self, target) {
+            (Self::I8 | Self::I16 | Self::I32 | Self::I64, Self::F64) => true,
+            (Self::U8 | Self::U16 | Self::U32 | Self::U64 | Self::F32, Self::F64) => true,
+            (Self::I8, Self::I16 | Self::I32 | Self::I64) => true,
+            (Self::I16, Self::I32 | Self::I64) | (Self::I32, Self::I64) => true,
+            (Self::U8, Self::U16 | Self::U32 | Self::U64) => true,
+            (Self::U16, Self::U32 | Self::U64) | (Self::U32, Self::U64) => true,
+            (Self::I128 | Self::U128, Self::F64) => true,
+            (Self::I8 | Self::I16 | Self::I32 | Self::I64, Self::I128) => true,
+            (Self::U8 | Self::U16 | Self::U32 | Self::U64, Self::I128 | Self::U128) => true,
+            (Self::Timestamp { unit, .. }, Self::Timestamp { unit: target, .. }) => target >= unit,
+            // Labels can be appended, but not removed or reordered
+            (Self::Enum(labels), Self::Enum(target)) => target.starts_with(labels),
+            (Self::Enum(_), Self::String) => true,
+            (
+                Self::Decimal { precision, scale },
+                Self::Decimal {
+                    precision: target_precision,
+                    scale: target_scale,
+                },
+            ) => {
+                target_scale >= scale
+                    && *target_precision as i16 - *target_scale as i16
+                        >= *precision as i16 - *scale as i16
+            }
+            (Self::List(elem), Self::List(target)) => elem.can_widen_to(target),
+            (Self::Map(key, value), Self::Map(target_key, target_value)) => {
+                key.can_widen_to(target_key) && value.can_widen_to(target_value)
+            }
+            // Members are matched by name; new members are null and removed ones are dropped
+            (Self::Struct(fields), Self::Struct(targets)) => {
+                targets.iter().all(|(name, target)| {
+                    fields
+                        .iter()
+                        .find(|(fname, _)| fname == name)
+                        .is_none_or(|(_, ftype)| ftype.can_widen_to(target))
+                })
+            }
+            _ => self == target,
+        }
+    }
+}
+
 ///Conversions
 impl From<&i32> for Value {
     fn from(i: &i32) -> Self {
@@ -62,6 +340,30 @@ impl From<&u64> for Value {
     }
 }
 
+impl From<i8> for Value {
+    fn from(i: i8) -> Self {
+        Self::I64(i as i64)
+    }
+}
+
+impl From<i16> for Value {
+    fn from(i: i16) -> Self {
+        Self::I64(i as i64)
+    }
+}
+
+impl From<u8> for Value {
+    fn from(i: u8) -> Self {
+        Self::U64(i as u64)
+    }
+}
+
+impl From<u16> for Value {
+    fn from(i: u16) -> Self {
+        Self::U64(i as u64)
+    }
+}
+
 impl From<i32> for Value {
     fn from(i: i32) -> Self {
         Self::I64(i as i64)
@@ -86,6 +388,30 @@ impl From<u64> for Value {
     }
 }
 
+impl From<i128> for Value {
+    fn from(i: i128) -> Self {
+        Self::I128(i)
+    }
+}
+
+impl From<u128> for Value {
+    fn from(i: u128) -> Self {
+        Self::U128(i)
+    }
+}
+
+impl From<Vec<Value>> for Value {
+    fn from(items: Vec<Value>) -> Self {
+        Self::List(items)
+    }
+}
+
+impl From<Vec<(Value, Value)>> for Value {
+    fn from(entries: Vec<(Value, Value)>) -> Self {
+        Self::Map(entries)
+    }
+}
+
 impl From<&bool> for Value {
     fn from(b: &bool) -> Self {
         Self::Bool(*b)
@@ -98,6 +424,12 @@ impl From<bool> for Value {
     }
 }
 
+impl From<f32> for Value {
+    fn from(f: f32) -> Self {
+        Self::F64(f as f64)
+    }
+}
+
 impl From<f64> for Value {
     fn from(f: f64) -> Self {
         Self::F64(f)
@@ -116,6 +448,54 @@ impl From<String> for Value {
     }
 }
 
+impl From<&[u8]> for Value {
+    fn from(b: &[u8]) -> Self {
+        Self::Bytes(b.to_vec())
+    }
+}
+
+impl From<Vec<u8>> for Value {
+    fn from(b: Vec<u8>) -> Self {
+        Self::Bytes(b)
+    }
+}
+
+impl From<Timestamp> for Value {
+    fn from(ts: Timestamp) -> Self {
+        Self::Timestamp(ts)
+    }
+}
+
+impl From<Date> for Value {
+    fn from(date: Date) -> Self {
+        Self::Date(date)
+    }
+}
+
+impl From<Time> for Value {
+    fn from(time: Time) -> Self {
+        Self::Time(time)
+    }
+}
+
+impl From<Duration> for Value {
+    fn from(duration: Duration) -> Self {
+        Self::Duration(duration)
+    }
+}
+
+impl From<Decimal> for Value {
+    fn from(decimal: Decimal) -> Self {
+        Self::Decimal(decimal)
+    }
+}
+
+impl From<Uuid> for Value {
+    fn from(uuid: Uuid) -> Self {
+        Self::Uuid(uuid)
+    }
+}
+
 #[cfg(feature = "json")]
 impl From<serde_json::Value> for Value {
     fn from(json: serde_json::Value) -> Self {
@@ -130,91 +510,568 @@ impl From<Box<serde_json::Value>> for Value {
     }
 }
 
-impl TryInto<i64> for Value {
+impl TryInto<i64> for Value {
+    type Error = ();
+
+    fn try_into(self) -> Result<i64, ()> {
+        if let Self::I64(i) = self {
+            Ok(i)
+        } else {
+            Err(())
+        }
+    }
+}
+
+impl TryInto<u64> for Value {
+    type Error = ();
+
+    fn try_into(self) -> Result<u64, ()> {
+        if let Self::U64(u) = self {
+            Ok(u)
+        } else {
+            Err(())
+        }
+    }
+}
+
+/// Narrow fields are read as 64-bit values, so these convert if the value fits
+impl TryInto<i8> for Value {
+    type Error = ();
+
+    fn try_into(self) -> Result<i8, ()> {
+        if let Self::I64(i) = self {
+            i.try_into().map_err(|_| ())
+        } else {
+            Err(())
+        }
+    }
+}
+
+impl TryInto<i16> for Value {
+    type Error = ();
+
+    fn try_into(self) -> Result<i16, ()> {
+        if let Self::I64(i) = self {
+            i.try_into().map_err(|_| ())
+        } else {
+            Err(())
+        }
+    }
+}
+
+impl TryInto<i32> for Value {
+    type Error = ();
+
+    fn try_into(self) -> Result<i32, ()> {
+        if let Self::I64(i) = self {
+            i.try_into().map_err(|_| ())
+        } else {
+            Err(())
+        }
+    }
+}
+
+impl TryInto<u8> for Value {
+    type Error = ();
+
+    fn try_into(self) -> Result<u8, ()> {
+        if let Self::U64(u) = self {
+            u.try_into().map_err(|_| ())
+        } else {
+            Err(())
+        }
+    }
+}
+
+impl TryInto<u16> for Value {
+    type Error = ();
+
+    fn try_into(self) -> Result<u16, ()> {
+        if let Self::U64(u) = self {
+            u.try_into().map_err(|_| ())
+        } else {
+            Err(())
+        }
+    }
+}
+
+impl TryInto<u32> for Value {
+    type Error = ();
+
+    fn try_into(self) -> Result<u32, ()> {
+        if let Self::U64(u) = self {
+            u.try_into().map_err(|_| ())
+        } else {
+            Err(())
+        }
+    }
+}
+
+impl TryInto<f32> for Value {
+    type Error = ();
+
+    fn try_into(self) -> Result<f32, ()> {
+        if let Self::F64(f) = self {
+            Ok(f as f32)
+        } else {
+            Err(())
+        }
+    }
+}
+
+impl TryInto<i128> for Value {
+    type Error = ();
+
+    fn try_into(self) -> Result<i128, ()> {
+        if let Self::I128(i) = self {
+            Ok(i)
+        } else {
+            Err(())
+        }
+    }
+}
+
+impl TryInto<u128> for Value {
+    type Error = ();
+
+    fn try_into(self) -> Result<u128, ()> {
+        if let Self::U128(u) = self {
+            Ok(u)
+        } else {
+            Err(())
+        }
+    }
+}
+
+impl TryInto<Vec<Value>> for Value {
+    type Error = ();
+
+    fn try_into(self) -> Result<Vec<Value>, ()> {
+        if let Self::List(items) = self {
+            Ok(items)
+        } else {
+            Err(())
+        }
+    }
+}
+
+impl TryInto<Vec<(Value, Value)>> for Value {
+    type Error = ();
+
+    fn try_into(self) -> Result<Vec<(Value, Value)>, ()> {
+        if let Self::Map(entries) = self {
+            Ok(entries)
+        } else {
+            Err(())
+        }
+    }
+}
+
+impl TryInto<bool> for Value {
+    type Error = ();
+
+    fn try_into(self) -> Result<bool, ()> {
+        if let Self::Bool(b) = self {
+            Ok(b)
+        } else {
+            Err(())
+        }
+    }
+}
+
+impl TryInto<f64> for Value {
+    type Error = ();
+
+    fn try_into(self) -> Result<f64, ()> {
+        if let Self::F64(f) = self {
+            Ok(f)
+        } else {
+            Err(())
+        }
+    }
+}
+
+impl TryInto<String> for Value {
+    type Error = ();
+
+    fn try_into(self) -> Result<String, ()> {
+        if let Self::String(s) = self {
+            Ok(s)
+        } else {
+            Err(())
+        }
+    }
+}
+
+impl TryInto<Vec<u8>> for Value {
+    type Error = ();
+
+    fn try_into(self) -> Result<Vec<u8>, ()> {
+        if let Self::Bytes(b) = self {
+            Ok(b)
+        } else {
+            Err(())
+        }
+    }
+}
+
+impl TryInto<Timestamp> for Value {
+    type Error = ();
+
+    fn try_into(self) -> Result<Timestamp, ()> {
+        if let Self::Timestamp(ts) = self {
+            Ok(ts)
+        } else {
+            Err(())
+        }
+    }
+}
+
+impl TryInto<Date> for Value {
     type Error = ();
 
-    fn try_into(self) -> Result<i64, ()> {
-        if let Self::I64(i) = self {
-            Ok(i)
+    fn try_into(self) -> Result<Date, ()> {
+        if let Self::Date(date) = self {
+            Ok(date)
         } else {
             Err(())
         }
     }
 }
 
-impl TryInto<u64> for Value {
+impl TryInto<Time> for Value {
     type Error = ();
 
-    fn try_into(self) -> Result<u64, ()> {
-        if let Self::U64(u) = self {
-            Ok(u)
+    fn try_into(self) -> Result<Time, ()> {
+        if let Self::Time(time) = self {
+            Ok(time)
         } else {
             Err(())
         }
     }
 }
 
-impl TryInto<bool> for Value {
+impl TryInto<Duration> for Value {
     type Error = ();
 
-    fn try_into(self) -> Result<bool, ()> {
-        if let Self::Bool(b) = self {
-            Ok(b)
+    fn try_into(self) -> Result<Duration, ()> {
+        if let Self::Duration(duration) = self {
+            Ok(duration)
         } else {
             Err(())
         }
     }
 }
 
-impl TryInto<f64> for Value {
+impl TryInto<Decimal> for Value {
     type Error = ();
 
-    fn try_into(self) -> Result<f64, ()> {
-        if let Self::F64(f) = self {
-            Ok(f)
+    fn try_into(self) -> Result<Decimal, ()> {
+        if let Self::Decimal(decimal) = self {
+            Ok(decimal)
         } else {
             Err(())
         }
     }
 }
 
-impl TryInto<String> for Value {
+impl TryInto<Uuid> for Value {
     type Error = ();
 
-    fn try_into(self) -> Result<String, ()> {
-        if let Self::String(s) = self {
-            Ok(s)
+    fn try_into(self) -> Result<Uuid, ()> {
+        if let Self::Uuid(uuid) = self {
+            Ok(uuid)
         } else {
             Err(())
         }
     }
 }
 
+/// Encodes the index of an enum label as a LEB128 varint
+/// The encoding of existing labels does not change when labels are appended.
+pub(crate) fn encode_variant(idx: usize) -> Vec<u8> {
+    let mut idx = idx as u64;
+    let mut buf = Vec::new();
+
+    loop {
+        let byte = (idx & 0x7f) as u8;
+        idx >>= 7;
+
+        if idx == 0 {
+            buf.push(byte);
+            return buf;
+        }
+        buf.push(byte | 0x80);
+    }
+}
+
+/// Finds the label of an enum value encoded by `encode_variant`
+pub(crate) fn decode_variant<'a>(
+    data: &[u8],
+    labels: &'a [String],
+) -> Result<&'a str, SchemaError> {
+    let mut idx = 0u64;
+
+    for (pos, byte) in data.iter().enumerate() {
+        if pos >= 10 {
+            break;
+        }
+
+        idx |= ((byte & 0x7f) as u64) << (7 * pos);
+
+        if byte & 0x80 == 0 {
+            if pos + 1 != data.len() {
+                break;
+            }
+
+            return match labels.get(idx as usize) {
+                Some(label) => Ok(label),
+                None => Err(SchemaError::EncodingError),
+            };
+        }
+    }
+
+    Err(SchemaError::EncodingError)
+}
+
 impl Value {
+    /// The type of this value, as it would be declared in a schema
+    /// Returns None for `Value::Null`, which can be stored in any nullable field
+    ///
+    /// Lists report the type of their first non-null element as their element type,
+    /// or `Bytes` if there is none, as the element type of an empty list is unknown.
+    /// Maps do the same for their key and value types, and structs for null members.
+    pub fn get_type(&self) -> Option<ValueType> {
+        let vtype = match self {
+            Self::Null => return None,
+            Self::String(_) => ValueType::String,
+            Self::Bytes(_) => ValueType::Bytes,
+            Self::F64(_) => ValueType::F64,
+            Self::I64(_) => ValueType::I64,
+            Self::U64(_) => ValueType::U64,
+            Self::I128(_) => ValueType::I128,
+            Self::U128(_) => ValueType::U128,
+            Self::Bool(_) => ValueType::Bool,
+            Self::Timestamp(ts) => ValueType::Timestamp {
+                unit: ts.unit(),
+                timezone: None,
+            },
+            Self::Date(_) => ValueType::Date,
+            Self::Time(_) => ValueType::Time,
+            Self::Duration(_) => ValueType::Duration,
+            Self::Decimal(decimal) => ValueType::Decimal {
+                precision: decimal.precision(),
+                scale: decimal.scale(),
+            },
+            Self::Uuid(_) => ValueType::Uuid,
+            Self::List(items) => {
+                let elem = items.iter().find_map(Value::get_type);
+                ValueType::List(Box::new(elem.unwrap_or(ValueType::Bytes)))
+            }
+            Self::Map(entries) => {
+                let key = entries.iter().find_map(|(key, _)| key.get_type());
+                let value = entries.iter().find_map(|(_, value)| value.get_type());
+
+                ValueType::Map(
+                    Box::new(key.unwrap_or(ValueType::Bytes)),
+                    Box::new(value.unwrap_or(ValueType::Bytes)),
+                )
+            }
+            Self::Struct(members) => ValueType::Struct(
+                members
+                    .iter()
+                    .map(|(name, value)| {
+                        (name.clone(), value.get_type().unwrap_or(ValueType::Bytes))
+                    })
+                    .collect(),
+            ),
+            #[cfg(feature = "json")]
+            Self::Json(_) => ValueType::Json,
+        };
+
+        Some(vtype)
+    }
+
+    pub fn is_null(&self) -> bool {
+        matches!(self, Self::Null)
+    }
+
+    /// Converts the value to a wider type without losing its meaning
+    /// Returns None if `target` is not the same as or wider than the value's type
+    ///
+    /// Narrow fields are read as 64-bit values, so a 64-bit value is returned
+    /// unchanged for narrow targets; use `ValueType::can_widen_to` to check
+    /// whether the field types are compatible.
+    pub fn widen(self, target: &ValueType) -> Option<Value> {
+        match (self, target) {
+            (value @ Self::I64(_), ValueType::I8 | ValueType::I16 | ValueType::I32) => Some(value),
+            (value @ Self::U64(_), ValueType::U8 | ValueType::U16 | ValueType::U32) => Some(value),
+            (value @ Self::F64(_), ValueType::F32) => Some(value),
+            (Self::I64(i), ValueType::F64) => Some(Self::F64(i as f64)),
+            (Self::U64(u), ValueType::F64) => Some(Self::F64(u as f64)),
+            (Self::I128(i), ValueType::F64) => Some(Self::F64(i as f64)),
+            (Self::U128(u), ValueType::F64) => Some(Self::F64(u as f64)),
+            (Self::I64(i), ValueType::I128) => Some(Self::I128(i as i128)),
+            (Self::U64(u), ValueType::I128) => Some(Self::I128(u as i128)),
+            (Self::U64(u), ValueType::U128) => Some(Self::U128(u as u128)),
+            (Self::Timestamp(ts), ValueType::Timestamp { unit, .. }) if *unit >= ts.unit() => {
+                ts.to_unit(*unit).map(Self::Timestamp)
+            }
+            (Self::Decimal(decimal), ValueType::Decimal { precision, scale }) => {
+                decimal.fit_to(*precision, *scale).map(Self::Decimal)
+            }
+            (Self::List(items), ValueType::List(elem)) => items
+                .into_iter()
+                .map(|item| item.widen(elem))
+                .collect::<Option<Vec<_>>>()
+                .map(Self::List),
+            (Self::Map(entries), ValueType::Map(key_type, value_type)) => entries
+                .into_iter()
+                .map(|(key, value)| Some((key.widen(key_type)?, value.widen(value_type)?)))
+                .collect::<Option<Vec<_>>>()
+                .map(Self::Map),
+            (Self::Struct(members), ValueType::Struct(fields)) => {
+                let mut members: Vec<_> = members.into_iter().map(Some).collect();
+
+                fields
+                    .iter()
+                    .map(|(fname, ftype)| {
+                        let member = members
+                            .iter_mut()
+                            .find(|member| matches!(member, Some((name, _)) if name == fname))
+                            .and_then(Option::take);
+                        let value = member.map_or(Self::Null, |(_, value)| value);
+
+                        Some((fname.clone(), value.widen(ftype)?))
+                    })
+                    .collect::<Option<Vec<_>>>()
+                    .map(Self::Struct)
+            }
+            (Self::String(label), ValueType::Enum(labels)) => {
+                labels.contains(&label).then_some(Self::String(label))
+            }
+            (Self::Null, _) => Some(Self::Null),
+            (value, target) => {
+                if value.get_type().as_ref() == Some(target) {
+                    Some(value)
+                } else {
+                    None
+                }
+            }
+        }
+    }
+
+    /// Encodes the value using the encoding of its own type
+    /// Panics for lists and maps whose elements do not all have the same type.
     pub fn serialize_inner(&self) -> Vec<u8> {
+        match self.try_serialize_inner() {
+            Ok(data) => data,
+            Err(err) => panic!("{}", err),
+        }
+    }
+
+    /// Same as `serialize_inner`, but fails instead of panicking
+    pub fn try_serialize_inner(&self) -> Result<Vec<u8>, SchemaError> {
         #[cfg(feature = "json")]
         if let Self::Json(v) = self {
-            return serde_json::to_vec(v).unwrap();
+            return serde_json::to_vec(v).map_err(|_| SchemaError::EncodingError);
+        }
+
+        if let Self::List(items) = self {
+            let Some(ValueType::List(elem)) = self.get_type() else {
+                unreachable!();
+            };
+            return serialize_list(items, &elem).ok_or(SchemaError::EncodingError);
+        }
+
+        if let Self::Map(entries) = self {
+            let Some(ValueType::Map(key_type, value_type)) = self.get_type() else {
+                unreachable!();
+            };
+            return serialize_map(entries, &key_type, &value_type)
+                .ok_or(SchemaError::EncodingError);
+        }
+
+        if let Self::Struct(members) = self {
+            let Some(ValueType::Struct(fields)) = self.get_type() else {
+                unreachable!();
+            };
+            return serialize_struct(members, &fields).ok_or(SchemaError::EncodingError);
         }
 
         match &self {
+            // Nulls are tracked by the entry, not stored in the field itself
+            Self::Null => Ok(Vec::new()),
             Self::String(v) => bincode::serialize(v),
+            Self::Bytes(v) => bincode::serialize(v),
             Self::F64(v) => bincode::serialize(v),
             Self::I64(v) => bincode::serialize(v),
             Self::U64(v) => bincode::serialize(v),
+            Self::I128(v) => bincode::serialize(v),
+            Self::U128(v) => bincode::serialize(v),
             Self::Bool(v) => bincode::serialize(v),
+            Self::Timestamp(ts) => bincode::serialize(&ts.value()),
+            Self::Date(date) => bincode::serialize(&date.days()),
+            Self::Time(time) => bincode::serialize(&time.nanos()),
+            Self::Duration(duration) => bincode::serialize(&duration.nanos()),
+            Self::Decimal(decimal) => bincode::serialize(&decimal.mantissa()),
+            Self::Uuid(uuid) => bincode::serialize(uuid.as_bytes()),
+            Self::List(_) | Self::Map(_) | Self::Struct(_) => panic!("invalid state"),
             #[cfg(feature = "json")]
             Self::Json(_) => panic!("invalid state"),
         }
-        .expect("Failed to serialize inner value")
+        .map_err(|_| SchemaError::EncodingError)
+    }
+
+    /// Same as serialize_inner, but stores numbers in the width of `vtype`
+    /// Returns None if the value does not fit into a field of that type.
+    pub(crate) fn serialize_as(&self, vtype: &ValueType) -> Option<Vec<u8>> {
+        let result = match (self, vtype) {
+            (Self::String(label), ValueType::Enum(labels)) => {
+                let idx = labels.iter().position(|other| other == label)?;
+                return Some(encode_variant(idx));
+            }
+            (Self::I64(i), ValueType::I8) => bincode::serialize(&i8::try_from(*i).ok()?),
+            (Self::I64(i), ValueType::I16) => bincode::serialize(&i16::try_from(*i).ok()?),
+            (Self::I64(i), ValueType::I32) => bincode::serialize(&i32::try_from(*i).ok()?),
+            (Self::U64(u), ValueType::U8) => bincode::serialize(&u8::try_from(*u).ok()?),
+            (Self::U64(u), ValueType::U16) => bincode::serialize(&u16::try_from(*u).ok()?),
+            (Self::U64(u), ValueType::U32) => bincode::serialize(&u32::try_from(*u).ok()?),
+            (Self::F64(f), ValueType::F32) => {
+                let narrow = *f as f32;
+                if narrow.is_infinite() && f.is_finite() {
+                    return None;
+                }
+                bincode::serialize(&narrow)
+            }
+            (Self::List(items), ValueType::List(elem)) => return serialize_list(items, elem),
+            (Self::Map(entries), ValueType::Map(key_type, value_type)) => {
+                return serialize_map(entries, key_type, value_type)
+            }
+            (Self::Struct(members), ValueType::Struct(fields)) => {
+                return serialize_struct(members, fields)
+            }
+            _ => return self.try_serialize_inner().ok(),
+        };
+
+        Some(result.expect("Failed to serialize inner value"))
     }
 
     pub fn from_bytes(data: &[u8], value_type: &ValueType) -> Result<Value, bincode::Error> {
         let val = match value_type {
+            ValueType::Enum(labels) => {
+                let label = decode_variant(data, labels)
+                    .map_err(|err| Box::new(bincode::ErrorKind::Custom(err.to_string())))?;
+                Value::String(label.to_string())
+            }
             ValueType::String => {
                 let v = bincode::deserialize(data)?;
                 Value::String(v)
             }
+            ValueType::Bytes => {
+                let v = bincode::deserialize(data)?;
+                Value::Bytes(v)
+            }
             ValueType::F64 => {
                 let v = bincode::deserialize(data)?;
                 Value::F64(v)
@@ -227,10 +1084,72 @@ impl Value {
                 let v = bincode::deserialize(data)?;
                 Value::U64(v)
             }
+            ValueType::I128 => {
+                let v = bincode::deserialize(data)?;
+                Value::I128(v)
+            }
+            ValueType::U128 => {
+                let v = bincode::deserialize(data)?;
+                Value::U128(v)
+            }
+            ValueType::I8 => Value::I64(bincode::deserialize::<i8>(data)? as i64),
+            ValueType::I16 => Value::I64(bincode::deserialize::<i16>(data)? as i64),
+            ValueType::I32 => Value::I64(bincode::deserialize::<i32>(data)? as i64),
+            ValueType::U8 => Value::U64(bincode::deserialize::<u8>(data)? as u64),
+            ValueType::U16 => Value::U64(bincode::deserialize::<u16>(data)? as u64),
+            ValueType::U32 => Value::U64(bincode::deserialize::<u32>(data)? as u64),
+            ValueType::F32 => Value::F64(bincode::deserialize::<f32>(data)? as f64),
             ValueType::Bool => {
                 let v = bincode::deserialize(data)?;
                 Value::Bool(v)
             }
+            ValueType::Timestamp { unit, .. } => {
+                let v = bincode::deserialize(data)?;
+                Value::Timestamp(Timestamp::new(v, *unit))
+            }
+            ValueType::Date => {
+                let v = bincode::deserialize(data)?;
+                Value::Date(Date::from_days(v))
+            }
+            ValueType::Time => {
+                let v = bincode::deserialize(data)?;
+                let Some(time) = Time::from_nanos(v) else {
+                    return Err(Box::new(bincode::ErrorKind::Custom(
+                        "Time out of range".to_string(),
+                    )));
+                };
+                Value::Time(time)
+            }
+            ValueType::Duration => {
+                let v = bincode::deserialize(data)?;
+                Value::Duration(Duration::from_nanos(v))
+            }
+            ValueType::Decimal { scale, .. } => {
+                let v = bincode::deserialize(data)?;
+                let Some(decimal) = Decimal::new(v, *scale) else {
+                    return Err(Box::new(bincode::ErrorKind::Custom(
+                        "Scale out of range".to_string(),
+                    )));
+                };
+                Value::Decimal(decimal)
+            }
+            ValueType::Uuid => {
+                let v = bincode::deserialize(data)?;
+                Value::Uuid(Uuid::from_bytes(v))
+            }
+            ValueType::List(elem) => {
+                let list = ListRef::from_bytes(data, elem).and_then(|list| list.to_value());
+                list.map_err(|err| Box::new(bincode::ErrorKind::Custom(err.to_string())))?
+            }
+            ValueType::Map(key_type, value_type) => {
+                let map =
+                    MapRef::from_bytes(data, key_type, value_type).and_then(|map| map.to_value());
+                map.map_err(|err| Box::new(bincode::ErrorKind::Custom(err.to_string())))?
+            }
+            ValueType::Struct(fields) => {
+                let value = StructRef::from_bytes(data, fields).and_then(|s| s.to_value());
+                value.map_err(|err| Box::new(bincode::ErrorKind::Custom(err.to_string())))?
+            }
             #[cfg(feature = "json")]
             ValueType::Json => {
                 let v = serde_json::from_slice(data).unwrap();
@@ -264,10 +1183,43 @@ impl FromPyObject<'_> for ValueType {
             Ok(ValueType::I64)
         } else if typename == "u64" {
             Ok(ValueType::U64)
+        } else if typename == "i128" {
+            Ok(ValueType::I128)
+        } else if typename == "u128" {
+            Ok(ValueType::U128)
+        } else if typename == "i8" {
+            Ok(ValueType::I8)
+        } else if typename == "i16" {
+            Ok(ValueType::I16)
+        } else if typename == "i32" {
+            Ok(ValueType::I32)
+        } else if typename == "u8" {
+            Ok(ValueType::U8)
+        } else if typename == "u16" {
+            Ok(ValueType::U16)
+        } else if typename == "u32" {
+            Ok(ValueType::U32)
+        } else if typename == "f32" {
+            Ok(ValueType::F32)
         } else if typename == "str" {
             Ok(ValueType::String)
         } else if typename == "bool" {
             Ok(ValueType::Bool)
+        } else if typename == "bytes" {
+            Ok(ValueType::Bytes)
+        } else if typename == "datetime" {
+            Ok(ValueType::Timestamp {
+                unit: TimeUnit::Microseconds,
+                timezone: None,
+            })
+        } else if typename == "date" {
+            Ok(ValueType::Date)
+        } else if typename == "UUID" {
+            Ok(ValueType::Uuid)
+        } else if typename == "time" {
+            Ok(ValueType::Time)
+        } else if typename == "timedelta" {
+            Ok(ValueType::Duration)
         } else {
             Err(PyErr::new::<pyexceptions::PyTypeError, _>(format!(
                 "Cannot convert to ValueType. Got '{}'.",
@@ -280,18 +1232,60 @@ impl FromPyObject<'_> for ValueType {
 #[cfg(feature = "python-bindings")]
 impl FromPyObject<'_> for Value {
     fn extract(obj: &PyAny) -> PyResult<Self> {
-        if let Ok(string) = PyAny::downcast::<PyString>(obj) {
+        if obj.is_none() {
+            Ok(Value::Null)
+        } else if let Ok(string) = PyAny::downcast::<PyString>(obj) {
             let rs_str: String = string.extract()?;
             Ok(rs_str.into())
+        } else if let Ok(pydatetime) = PyAny::downcast::<PyDateTime>(obj) {
+            // Must be checked before PyDate, as datetime is a subclass of date
+            timestamp_from_python(pydatetime).map(Value::Timestamp)
+        } else if let Ok(pydate) = PyAny::downcast::<PyDate>(obj) {
+            Date::from_ymd(
+                pydate.get_year() as i64,
+                pydate.get_month() as u32,
+                pydate.get_day() as u32,
+            )
+            .map(Value::Date)
+            .ok_or_else(|| PyErr::new::<pyexceptions::PyValueError, _>("Date out of range"))
+        } else if let Ok(pytime) = PyAny::downcast::<PyTime>(obj) {
+            // The timezone of a time of day is ignored
+            Time::from_hms_nano(
+                pytime.get_hour() as u32,
+                pytime.get_minute() as u32,
+                pytime.get_second() as u32,
+                pytime.get_microsecond() * 1_000,
+            )
+            .map(Value::Time)
+            .ok_or_else(|| PyErr::new::<pyexceptions::PyValueError, _>("Time out of range"))
+        } else if let Ok(pydelta) = PyAny::downcast::<PyDelta>(obj) {
+            Ok(Value::Duration(duration_from_python(pydelta)?))
+        } else if let Ok(pybytes) = PyAny::downcast::<PyBytes>(obj) {
+            Ok(pybytes.as_bytes().into())
         } else if let Ok(pyfloat) = PyAny::downcast::<PyFloat>(obj) {
             let f: f64 = pyfloat.extract()?;
             Ok(f.into())
         } else if let Ok(pyint) = PyAny::downcast::<PyLong>(obj) {
-            let i: i64 = pyint.extract()?;
-            Ok(i.into())
+            int_from_python(pyint)
         } else if let Ok(pyint) = PyAny::downcast::<PyInt>(obj) {
-            let i: i64 = pyint.extract()?;
-            Ok(i.into())
+            int_from_python(pyint)
+        } else if obj.as_borrowed().is_instance(&decimal_class(obj.py())?)? {
+            let s: String = obj.str()?.extract()?;
+            Decimal::parse(&s)
+                .map(Value::Decimal)
+                .map_err(|err| PyErr::new::<pyexceptions::PyValueError, _>(err.to_string()))
+        } else if obj.as_borrowed().is_instance(&uuid_class(obj.py())?)? {
+            let bytes: [u8; 16] = obj.getattr("bytes")?.extract()?;
+            Ok(Value::Uuid(Uuid::from_bytes(bytes)))
+        } else if let Ok(pylist) = PyAny::downcast::<PyList>(obj) {
+            let items = pylist.iter().map(Value::extract).collect::<PyResult<_>>()?;
+            Ok(Value::List(items))
+        } else if let Ok(pydict) = PyAny::downcast::<PyDict>(obj) {
+            let entries = pydict
+                .iter()
+                .map(|(key, value)| Ok((key.extract()?, value.extract()?)))
+                .collect::<PyResult<_>>()?;
+            Ok(Value::Map(entries))
         } else {
             Err(PyErr::new::<pyexceptions::PyTypeError, _>(
                 "Failed to convert PyObject to Value",
@@ -304,17 +1298,197 @@ impl FromPyObject<'_> for Value {
 impl IntoPy<PyObject> for Value {
     fn into_py(self, py: Python) -> PyObject {
         match self {
+            Value::Null => py.None(),
             Value::String(string) => string.into_py(py),
+            Value::Bytes(bytes) => PyBytes::new_bound(py, &bytes).into_py(py),
             Value::Bool(b) => b.into_py(py),
             Value::I64(integer) => integer.into_py(py),
             Value::F64(f) => f.into_py(py),
             Value::U64(u) => u.into_py(py),
+            Value::I128(i) => i.into_py(py),
+            Value::U128(u) => u.into_py(py),
+            Value::Timestamp(ts) => timestamp_to_python(py, ts),
+            Value::Date(date) => {
+                let (year, month, day) = date.to_ymd();
+
+                // Dates outside of the range of `date` are returned as strings
+                match PyDate::new_bound(py, year as i32, month as u8, day as u8) {
+                    Ok(py_date) => py_date.into_py(py),
+                    Err(_) => date.to_string().into_py(py),
+                }
+            }
+            Value::Time(time) => {
+                let (hour, minute, second, nanos) = time.to_hms_nano();
+                PyTime::new_bound(
+                    py,
+                    hour as u8,
+                    minute as u8,
+                    second as u8,
+                    nanos / 1_000,
+                    None,
+                )
+                .unwrap()
+                .into_py(py)
+            }
+            Value::Duration(duration) => {
+                let micros = duration.nanos().div_euclid(1_000);
+                let seconds = micros.div_euclid(1_000_000);
+
+                PyDelta::new_bound(
+                    py,
+                    seconds.div_euclid(86_400) as i32,
+                    seconds.rem_euclid(86_400) as i32,
+                    micros.rem_euclid(1_000_000) as i32,
+                    false,
+                )
+                .unwrap()
+                .into_py(py)
+            }
+            Value::Decimal(decimal) => decimal_class(py)
+                .and_then(|class| class.call1((decimal.to_string(),)))
+                .expect("Failed to create decimal.Decimal")
+                .into_py(py),
+            Value::Uuid(uuid) => {
+                let kwargs = PyDict::new_bound(py);
+                kwargs
+                    .set_item("bytes", PyBytes::new_bound(py, uuid.as_bytes()))
+                    .unwrap();
+
+                uuid_class(py)
+                    .and_then(|class| class.call((), Some(&kwargs)))
+                    .expect("Failed to create uuid.UUID")
+                    .into_py(py)
+            }
+            Value::List(items) => {
+                let items = items.into_iter().map(|item| item.into_py(py));
+                PyList::new_bound(py, items).into_py(py)
+            }
+            Value::Map(entries) => {
+                let dict = PyDict::new_bound(py);
+
+                for (key, value) in entries {
+                    dict.set_item(key.into_py(py), value.into_py(py))
+                        .expect("Failed to insert into dict");
+                }
+
+                dict.into_py(py)
+            }
+            Value::Struct(members) => {
+                let dict = PyDict::new_bound(py);
+
+                for (name, value) in members {
+                    dict.set_item(name, value.into_py(py))
+                        .expect("Failed to insert into dict");
+                }
+
+                dict.into_py(py)
+            }
             #[cfg(feature = "json")]
             Value::Json(v) => json_to_python(py, *v),
         }
     }
 }
 
+/// Uses the narrowest of I64, U64, I128 and U128 that can hold the integer
+#[cfg(feature = "python-bindings")]
+fn int_from_python(obj: &PyLong) -> PyResult<Value> {
+    if let Ok(i) = obj.extract::<i64>() {
+        Ok(Value::I64(i))
+    } else if let Ok(u) = obj.extract::<u64>() {
+        Ok(Value::U64(u))
+    } else if let Ok(i) = obj.extract::<i128>() {
+        Ok(Value::I128(i))
+    } else {
+        obj.extract::<u128>().map(Value::U128)
+    }
+}
+
+#[cfg(feature = "python-bindings")]
+fn decimal_class(py: Python) -> PyResult<Bound<PyAny>> {
+    py.import_bound("decimal")?.getattr("Decimal")
+}
+
+#[cfg(feature = "python-bindings")]
+fn uuid_class(py: Python) -> PyResult<Bound<PyAny>> {
+    py.import_bound("uuid")?.getattr("UUID")
+}
+
+/// Python only supports microseconds, so finer timestamps are truncated
+/// The resulting datetime is always timezone-aware and in UTC. Timestamps
+/// outside of the range of `datetime` are returned as strings instead.
+#[cfg(feature = "python-bindings")]
+fn timestamp_to_python(py: Python, ts: Timestamp) -> PyObject {
+    let Some((date, time)) = ts.to_date_time() else {
+        return ts.to_string().into_py(py);
+    };
+    let (year, month, day) = date.to_ymd();
+    let (hour, minute, second, nanos) = time.to_hms_nano();
+
+    let result = PyDateTime::new_bound(
+        py,
+        year as i32,
+        month as u8,
+        day as u8,
+        hour as u8,
+        minute as u8,
+        second as u8,
+        nanos / 1_000,
+        Some(&timezone_utc_bound(py)),
+    );
+
+    match result {
+        Ok(datetime) => datetime.into_py(py),
+        Err(_) => ts.to_string().into_py(py),
+    }
+}
+
+/// Naive datetimes are assumed to be in UTC
+#[cfg(feature = "python-bindings")]
+fn timestamp_from_python(obj: &PyDateTime) -> PyResult<Timestamp> {
+    let out_of_range = || PyErr::new::<pyexceptions::PyValueError, _>("Timestamp out of range");
+
+    let date = Date::from_ymd(
+        obj.get_year() as i64,
+        obj.get_month() as u32,
+        obj.get_day() as u32,
+    )
+    .ok_or_else(out_of_range)?;
+
+    let time = Time::from_hms_nano(
+        obj.get_hour() as u32,
+        obj.get_minute() as u32,
+        obj.get_second() as u32,
+        obj.get_microsecond() * 1_000,
+    )
+    .ok_or_else(out_of_range)?;
+
+    let ts =
+        Timestamp::from_date_time(date, time, TimeUnit::Microseconds).ok_or_else(out_of_range)?;
+
+    let offset = obj.call_method0("utcoffset")?;
+    if offset.is_none() {
+        return Ok(ts);
+    }
+
+    let offset = duration_from_python(offset.downcast::<PyDelta>()?)?;
+    ts.value()
+        .checked_sub(offset.nanos() / 1_000)
+        .map(|value| Timestamp::new(value, TimeUnit::Microseconds))
+        .ok_or_else(out_of_range)
+}
+
+#[cfg(feature = "python-bindings")]
+fn duration_from_python(obj: &PyDelta) -> PyResult<Duration> {
+    let seconds = obj.get_days() as i64 * 86_400 + obj.get_seconds() as i64;
+    let micros = obj.get_microseconds() as i64;
+
+    seconds
+        .checked_mul(1_000_000_000)
+        .and_then(|nanos| nanos.checked_add(micros * 1_000))
+        .map(Duration::from_nanos)
+        .ok_or_else(|| PyErr::new::<pyexceptions::PyOverflowError, _>("Duration out of range"))
+}
+
 #[cfg(all(feature = "json", feature = "python-bindings"))]
 fn json_to_python(py: Python, json_value: serde_json::Value) -> PyObject {
     match json_value {
@@ -341,10 +1515,12 @@ fn json_to_python(py: Python, json_value: serde_json::Value) -> PyObject {
         serde_json::Value::Null => py.None(),
         serde_json::Value::Bool(b) => b.into_py(py),
         serde_json::Value::Number(n) => {
-            if n.is_i64() {
-                n.as_i64().unwrap().into_py(py)
+            if let Some(i) = n.as_i64() {
+                i.into_py(py)
+            } else if let Some(u) = n.as_u64() {
+                u.into_py(py)
             } else {
-                n.as_u64().unwrap().into_py(py)
+                n.as_f64().unwrap().into_py(py)
             }
         }
         serde_json::Value::String(s) => s.into_py(py),
@@ -369,16 +1545,19 @@ pub fn python_to_json(py: Python, obj: Bound<'_, PyAny>) -> PyResult<serde_json:
         Ok(f.into())
     } else if let Ok(pyint) = Bound::downcast::<PyLong>(&obj) {
         // Pyint might not fit into signed integer so switch to u64 if needed
+        // JSON numbers are limited to 64 bits, so larger ints are rejected
         match pyint.extract::<i64>() {
             Ok(i) => Ok(i.into()),
-            Err(err) => {
-                if err.is_instance_of::<pyexceptions::PyOverflowError>(py) {
-                    let u: u64 = pyint.extract()?;
-                    Ok(u.into())
-                } else {
-                    Err(err)
+            Err(err) if err.is_instance_of::<pyexceptions::PyOverflowError>(py) => {
+                match pyint.extract::<u64>() {
+                    Ok(u) => Ok(u.into()),
+                    Err(_) => Err(PyErr::new::<pyexceptions::PyOverflowError, _>(format!(
+                        "Integer does not fit into a JSON number: {}; use an I128 or U128 field instead",
+                        pyint
+                    ))),
                 }
             }
+            Err(err) => Err(err),
         }
     } else if let Ok(pyint) = Bound::downcast::<PyInt>(&obj) {
         let i: i64 = pyint.extract()?;
@@ -410,7 +1589,10 @@ pub fn python_to_json(py: Python, obj: Bound<'_, PyAny>) -> PyResult<serde_json:
 #[cfg(test)]
 mod tests {
     use super::{json_to_python, python_to_json, Value, ValueType};
-    use pyo3::Python;
+    use crate::datetime::{Date, Duration, Time, Timestamp};
+    use crate::decimal::Decimal;
+    use crate::uuid::Uuid;
+    use pyo3::{IntoPy, Python};
     use serde_json::json;
 
     #[test]
@@ -425,17 +1607,285 @@ mod tests {
         assert_eq!(val, val2);
     }
 
+    #[test]
+    fn serialize_bytes() {
+        let val = Value::Bytes(vec![0, 1, 2, 255]);
+        let data = val.serialize_inner();
+
+        assert_eq!(Value::from_bytes(&data, &ValueType::Bytes).unwrap(), val);
+    }
+
+    #[test]
+    fn serialize_uuid() {
+        let val = Value::Uuid(Uuid::from_u128(0x67e5504410b1426f9247bb680e5fe0c8));
+        let data = val.serialize_inner();
+
+        assert_eq!(data.len(), 16);
+        assert_eq!(Value::from_bytes(&data, &ValueType::Uuid).unwrap(), val);
+    }
+
+    #[test]
+    fn serialize_narrow() {
+        let data = Value::I64(-5).serialize_as(&ValueType::I16).unwrap();
+        assert_eq!(data.len(), 2);
+        assert_eq!(
+            Value::from_bytes(&data, &ValueType::I16).unwrap(),
+            Value::I64(-5)
+        );
+
+        let data = Value::F64(1.5).serialize_as(&ValueType::F32).unwrap();
+        assert_eq!(data.len(), 4);
+        assert_eq!(
+            Value::from_bytes(&data, &ValueType::F32).unwrap(),
+            Value::F64(1.5)
+        );
+
+        assert_eq!(Value::U64(256).serialize_as(&ValueType::U8), None);
+        assert_eq!(Value::I64(i64::MIN).serialize_as(&ValueType::I32), None);
+        assert_eq!(Value::F64(1e300).serialize_as(&ValueType::F32), None);
+    }
+
+    #[test]
+    fn int_to_py() {
+        pyo3::prepare_freethreaded_python();
+
+        Python::with_gil(|py| {
+            let values = [
+                Value::I64(-5),
+                Value::U64(u64::MAX),
+                Value::I128(i128::MIN),
+                Value::U128(u128::MAX),
+            ];
+
+            for val in values {
+                let py_obj = val.clone().into_py(py);
+                let val2: Value = py_obj.extract(py).unwrap();
+
+                assert_eq!(val, val2);
+            }
+
+            let too_large = py.eval_bound("2 ** 128", None, None).unwrap();
+            assert!(too_large.into_py(py).extract::<Value>(py).is_err());
+        });
+    }
+
+    #[test]
+    fn serialize_list() {
+        let val = Value::List(vec![1i64.into(), (-2i64).into(), 3i64.into()]);
+
+        // Fixed-size elements are stored without length prefixes
+        let data = val
+            .serialize_as(&ValueType::List(Box::new(ValueType::I16)))
+            .unwrap();
+        assert_eq!(data.len(), 4 + 3 * 2);
+        assert_eq!(
+            Value::from_bytes(&data, &ValueType::List(Box::new(ValueType::I16))).unwrap(),
+            val
+        );
+
+        let val = Value::List(vec!["foo".into(), "".into()]);
+        let data = val.serialize_inner();
+        assert_eq!(
+            Value::from_bytes(&data, &ValueType::List(Box::new(ValueType::String))).unwrap(),
+            val
+        );
+    }
+
+    #[test]
+    fn list_to_py() {
+        pyo3::prepare_freethreaded_python();
+
+        Python::with_gil(|py| {
+            let val = Value::List(vec!["a".into(), Value::List(vec![1i64.into()])]);
+            let py_obj = val.clone().into_py(py);
+            let val2: Value = py_obj.extract(py).unwrap();
+
+            assert_eq!(val, val2);
+        });
+    }
+
+    #[test]
+    fn serialize_map() {
+        let vtype = ValueType::Map(Box::new(ValueType::String), Box::new(ValueType::U16));
+        let map1 = Value::Map(vec![("b".into(), 2u64.into()), ("a".into(), 1u64.into())]);
+        let map2 = Value::Map(vec![("a".into(), 1u64.into()), ("b".into(), 2u64.into())]);
+
+        // Entries are sorted by key, independent of their original order
+        let data = map1.serialize_as(&vtype).unwrap();
+        assert_eq!(data, map2.serialize_as(&vtype).unwrap());
+        assert_eq!(Value::from_bytes(&data, &vtype).unwrap(), map2);
+
+        let empty = Value::Map(vec![]);
+        assert_eq!(
+            Value::from_bytes(&empty.serialize_as(&vtype).unwrap(), &vtype).unwrap(),
+            empty
+        );
+    }
+
+    #[test]
+    fn serialize_enum() {
+        let labels: Vec<String> = (0..200).map(|idx| format!("label{}", idx)).collect();
+        let vtype = ValueType::Enum(labels);
+
+        let data = Value::from("label3").serialize_as(&vtype).unwrap();
+        assert_eq!(data, vec![3]);
+        assert_eq!(Value::from_bytes(&data, &vtype).unwrap(), "label3".into());
+
+        let data = Value::from("label150").serialize_as(&vtype).unwrap();
+        assert_eq!(data, vec![150, 1]);
+        assert_eq!(Value::from_bytes(&data, &vtype).unwrap(), "label150".into());
+
+        assert_eq!(Value::from("other").serialize_as(&vtype), None);
+        assert!(Value::from_bytes(&[250, 1], &vtype).is_err());
+    }
+
+    #[test]
+    fn serialize_struct() {
+        let vtype = ValueType::Struct(vec![
+            ("city".to_string(), ValueType::String),
+            ("zip".to_string(), ValueType::U32),
+        ]);
+
+        // Members are matched by name, and missing members are null
+        let val = Value::Struct(vec![("zip".to_string(), 8000u64.into())]);
+        let data = val.serialize_as(&vtype).unwrap();
+        assert_eq!(
+            Value::from_bytes(&data, &vtype).unwrap(),
+            Value::Struct(vec![
+                ("city".to_string(), Value::Null),
+                ("zip".to_string(), 8000u64.into()),
+            ])
+        );
+    }
+
+    #[test]
+    fn map_to_py() {
+        pyo3::prepare_freethreaded_python();
+
+        Python::with_gil(|py| {
+            let val = Value::Map(vec![
+                ("a".into(), Value::List(vec![1i64.into()])),
+                ("b".into(), Value::Null),
+            ]);
+            let py_obj = val.clone().into_py(py);
+            let val2: Value = py_obj.extract(py).unwrap();
+
+            assert_eq!(val, val2);
+        });
+    }
+
+    #[test]
+    fn bytes_to_py() {
+        pyo3::prepare_freethreaded_python();
+
+        Python::with_gil(|py| {
+            let val = Value::Bytes(vec![0, 1, 2, 255]);
+            let py_obj = val.clone().into_py(py);
+            let val2: Value = py_obj.extract(py).unwrap();
+
+            assert_eq!(val, val2);
+        });
+    }
+
+    #[test]
+    fn datetime_to_py() {
+        pyo3::prepare_freethreaded_python();
+
+        Python::with_gil(|py| {
+            let values = [
+                Value::Timestamp(Timestamp::parse("2024-03-01T10:30:05.123456Z").unwrap()),
+                Value::Date(Date::parse("1969-07-20").unwrap()),
+                Value::Time(Time::parse("20:17:40").unwrap()),
+                Value::Duration(Duration::parse("-P1DT0.5S").unwrap()),
+            ];
+
+            for val in values {
+                let py_obj = val.clone().into_py(py);
+                let val2: Value = py_obj.extract(py).unwrap();
+
+                assert_eq!(val, val2);
+            }
+
+            // Aware datetimes are converted to UTC
+            let py_obj = py
+                .eval_bound(
+                    "__import__('datetime').datetime.fromisoformat('2024-03-01T12:30:05+02:00')",
+                    None,
+                    None,
+                )
+                .unwrap();
+            let val: Value = py_obj.into_py(py).extract(py).unwrap();
+            assert_eq!(
+                val,
+                Value::Timestamp(Timestamp::parse("2024-03-01T10:30:05Z").unwrap())
+            );
+
+            // Values that Python cannot represent are converted to strings
+            let date = Date::parse("-0001-01-01").unwrap();
+            let py_obj = Value::Date(date).into_py(py);
+            assert_eq!(py_obj.extract::<String>(py).unwrap(), "-0001-01-01");
+
+            let ts = Timestamp::parse("10000-01-01T00:00:00Z").unwrap();
+            let py_obj = Value::Timestamp(ts).into_py(py);
+            assert_eq!(
+                py_obj.extract::<String>(py).unwrap(),
+                "10000-01-01T00:00:00Z"
+            );
+        });
+    }
+
+    #[test]
+    fn decimal_to_py() {
+        pyo3::prepare_freethreaded_python();
+
+        Python::with_gil(|py| {
+            let val = Value::Decimal(Decimal::parse("-1234.5678").unwrap());
+            let py_obj = val.clone().into_py(py);
+            assert_eq!(py_obj.to_string(), "-1234.5678");
+
+            let val2: Value = py_obj.extract(py).unwrap();
+            assert_eq!(val, val2);
+        });
+    }
+
+    #[test]
+    fn uuid_to_py() {
+        pyo3::prepare_freethreaded_python();
+
+        Python::with_gil(|py| {
+            let val = Value::Uuid(Uuid::parse("67e55044-10b1-426f-9247-bb680e5fe0c8").unwrap());
+            let py_obj = val.clone().into_py(py);
+            assert_eq!(py_obj.to_string(), "67e55044-10b1-426f-9247-bb680e5fe0c8");
+
+            let val2: Value = py_obj.extract(py).unwrap();
+            assert_eq!(val, val2);
+        });
+    }
+
     #[test]
     fn json_to_py() {
         pyo3::prepare_freethreaded_python();
 
         Python::with_gil(|py| {
-            let json_obj =
-                json!({ "value1": u64::MAX, "value2": i64::MIN, "list": ["a", "b", "c"] });
+            let json_obj = json!({
+                "value1": u64::MAX,
+                "value2": i64::MIN,
+                "value3": 0.5,
+                "list": ["a", "b", "c"]
+            });
             let py_obj = json_to_python(py, json_obj.clone());
             let json_obj2 = python_to_json(py, py_obj.into_bound(py)).unwrap();
 
             assert_eq!(json_obj, json_obj2);
+
+            // Ints beyond 64 bits cannot be represented as JSON numbers
+            for big in [u64::MAX as i128 + 1, i64::MIN as i128 - 1] {
+                let py_obj = big.into_py(py).into_bound(py);
+                let err = python_to_json(py, py_obj).unwrap_err();
+
+                assert!(err.is_instance_of::<pyo3::exceptions::PyOverflowError>(py));
+                assert!(err.to_string().contains(&big.to_string()));
+            }
         });
     }
 }
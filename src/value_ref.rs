@@ -0,0 +1,302 @@
+use crate::datetime::{Date, Duration, Time, Timestamp};
+use crate::decimal::Decimal;
+use crate::list::ListRef;
+use crate::map::MapRef;
+use crate::record::StructRef;
+use crate::uuid::Uuid;
+use crate::value::decode_variant;
+use crate::{SchemaError, Value, ValueType};
+
+/// A value borrowed from the encoded fields of a `DataEntry`
+///
+/// Unlike `Value`, creating a ValueRef does not allocate, which
+/// makes it cheaper to inspect a few fields of many entries.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ValueRef<'a> {
+    Null,
+    String(&'a str),
+    Bytes(&'a [u8]),
+    F64(f64),
+    I64(i64),
+    U64(u64),
+    I128(i128),
+    U128(u128),
+    Bool(bool),
+    Timestamp(Timestamp),
+    Date(Date),
+    Time(Time),
+    Duration(Duration),
+    Decimal(Decimal),
+    Uuid(Uuid),
+    List(ListRef<'a>),
+    Map(MapRef<'a>),
+    Struct(StructRef<'a>),
+    /// The serialized JSON text
+    #[cfg(feature = "json")]
+    Json(&'a str),
+}
+
+fn fixed<const N: usize>(data: &[u8]) -> Result<[u8; N], SchemaError> {
+    data.try_into().map_err(|_| SchemaError::EncodingError)
+}
+
+/// Splits off the u64 length prefix that bincode puts in front of strings and byte arrays
+fn length_prefixed(data: &[u8]) -> Result<&[u8], SchemaError> {
+    if data.len() < 8 {
+        return Err(SchemaError::EncodingError);
+    }
+
+    let (len, bytes) = data.split_at(8);
+    if u64::from_le_bytes(fixed(len)?) != bytes.len() as u64 {
+        return Err(SchemaError::EncodingError);
+    }
+
+    Ok(bytes)
+}
+
+impl<'a> ValueRef<'a> {
+    /// Reads a value encoded by `Value::serialize_inner` without copying it
+    pub fn from_bytes(data: &'a [u8], value_type: &'a ValueType) -> Result<Self, SchemaError> {
+        let val = match value_type {
+            ValueType::Enum(labels) => Self::String(decode_variant(data, labels)?),
+            ValueType::String => {
+                let bytes = length_prefixed(data)?;
+                let s = std::str::from_utf8(bytes).map_err(|_| SchemaError::EncodingError)?;
+                Self::String(s)
+            }
+            ValueType::Bytes => Self::Bytes(length_prefixed(data)?),
+            ValueType::F64 => Self::F64(f64::from_le_bytes(fixed(data)?)),
+            ValueType::I64 => Self::I64(i64::from_le_bytes(fixed(data)?)),
+            ValueType::U64 => Self::U64(u64::from_le_bytes(fixed(data)?)),
+            ValueType::I128 => Self::I128(i128::from_le_bytes(fixed(data)?)),
+            ValueType::U128 => Self::U128(u128::from_le_bytes(fixed(data)?)),
+            ValueType::I8 => Self::I64(i8::from_le_bytes(fixed(data)?) as i64),
+            ValueType::I16 => Self::I64(i16::from_le_bytes(fixed(data)?) as i64),
+            ValueType::I32 => Self::I64(i32::from_le_bytes(fixed(data)?) as i64),
+            ValueType::U8 => Self::U64(u8::from_le_bytes(fixed(data)?) as u64),
+            ValueType::U16 => Self::U64(u16::from_le_bytes(fixed(data)?) as u64),
+            ValueType::U32 => Self::U64(u32::from_le_bytes(fixed(data)?) as u64),
+            ValueType::F32 => Self::F64(f32::from_le_bytes(fixed(data)?) as f64),
+            ValueType::Bool => match data {
+                [0] => Self::Bool(false),
+                [1] => Self::Bool(true),
+                _ => return Err(SchemaError::EncodingError),
+            },
+            ValueType::Timestamp { unit, .. } => {
+                Self::Timestamp(Timestamp::new(i64::from_le_bytes(fixed(data)?), *unit))
+            }
+            ValueType::Date => Self::Date(Date::from_days(i32::from_le_bytes(fixed(data)?))),
+            ValueType::Time => {
+                let time = Time::from_nanos(i64::from_le_bytes(fixed(data)?));
+                Self::Time(time.ok_or(SchemaError::EncodingError)?)
+            }
+            ValueType::Duration => {
+                Self::Duration(Duration::from_nanos(i64::from_le_bytes(fixed(data)?)))
+            }
+            ValueType::Decimal { scale, .. } => {
+                let decimal = Decimal::new(i128::from_le_bytes(fixed(data)?), *scale);
+                Self::Decimal(decimal.ok_or(SchemaError::EncodingError)?)
+            }
+            ValueType::Uuid => Self::Uuid(Uuid::from_bytes(fixed(data)?)),
+            ValueType::List(elem) => Self::List(ListRef::from_bytes(data, elem)?),
+            ValueType::Map(key_type, value_type) => {
+                Self::Map(MapRef::from_bytes(data, key_type, value_type)?)
+            }
+            ValueType::Struct(fields) => Self::Struct(StructRef::from_bytes(data, fields)?),
+            #[cfg(feature = "json")]
+            ValueType::Json => {
+                let s = std::str::from_utf8(data).map_err(|_| SchemaError::EncodingError)?;
+                Self::Json(s)
+            }
+        };
+
+        Ok(val)
+    }
+
+    pub fn get_type(&self) -> Option<ValueType> {
+        let vtype = match self {
+            Self::Null => return None,
+            Self::String(_) => ValueType::String,
+            Self::Bytes(_) => ValueType::Bytes,
+            Self::F64(_) => ValueType::F64,
+            Self::I64(_) => ValueType::I64,
+            Self::U64(_) => ValueType::U64,
+            Self::I128(_) => ValueType::I128,
+            Self::U128(_) => ValueType::U128,
+            Self::Bool(_) => ValueType::Bool,
+            Self::Timestamp(ts) => ValueType::Timestamp {
+                unit: ts.unit(),
+                timezone: None,
+            },
+            Self::Date(_) => ValueType::Date,
+            Self::Time(_) => ValueType::Time,
+            Self::Duration(_) => ValueType::Duration,
+            Self::Decimal(decimal) => ValueType::Decimal {
+                precision: decimal.precision(),
+                scale: decimal.scale(),
+            },
+            Self::Uuid(_) => ValueType::Uuid,
+            Self::List(list) => ValueType::List(Box::new(list.element_type().clone())),
+            Self::Map(map) => ValueType::Map(
+                Box::new(map.key_type().clone()),
+                Box::new(map.value_type().clone()),
+            ),
+            Self::Struct(record) => ValueType::Struct(record.field_types().clone()),
+            #[cfg(feature = "json")]
+            Self::Json(_) => ValueType::Json,
+        };
+
+        Some(vtype)
+    }
+
+    pub fn is_null(&self) -> bool {
+        matches!(self, Self::Null)
+    }
+
+    pub fn as_str(&self) -> Option<&'a str> {
+        if let Self::String(s) = self {
+            Some(s)
+        } else {
+            None
+        }
+    }
+
+    pub fn as_list(&self) -> Option<ListRef<'a>> {
+        if let Self::List(list) = self {
+            Some(*list)
+        } else {
+            None
+        }
+    }
+
+    pub fn as_map(&self) -> Option<MapRef<'a>> {
+        if let Self::Map(map) = self {
+            Some(*map)
+        } else {
+            None
+        }
+    }
+
+    pub fn as_struct(&self) -> Option<StructRef<'a>> {
+        if let Self::Struct(record) = self {
+            Some(*record)
+        } else {
+            None
+        }
+    }
+
+    pub fn as_bytes(&self) -> Option<&'a [u8]> {
+        if let Self::Bytes(b) = self {
+            Some(b)
+        } else {
+            None
+        }
+    }
+
+    /// Copies the value into an owned `Value`
+    pub fn to_value(&self) -> Result<Value, SchemaError> {
+        let val = match self {
+            Self::Null => Value::Null,
+            Self::String(s) => Value::String(s.to_string()),
+            Self::Bytes(b) => Value::Bytes(b.to_vec()),
+            Self::F64(f) => Value::F64(*f),
+            Self::I64(i) => Value::I64(*i),
+            Self::U64(u) => Value::U64(*u),
+            Self::I128(i) => Value::I128(*i),
+            Self::U128(u) => Value::U128(*u),
+            Self::Bool(b) => Value::Bool(*b),
+            Self::Timestamp(ts) => Value::Timestamp(*ts),
+            Self::Date(date) => Value::Date(*date),
+            Self::Time(time) => Value::Time(*time),
+            Self::Duration(duration) => Value::Duration(*duration),
+            Self::Decimal(decimal) => Value::Decimal(*decimal),
+            Self::Uuid(uuid) => Value::Uuid(*uuid),
+            Self::List(list) => list.to_value()?,
+            Self::Map(map) => map.to_value()?,
+            Self::Struct(record) => record.to_value()?,
+            #[cfg(feature = "json")]
+            Self::Json(s) => {
+                let json = serde_json::from_str(s).map_err(|_| SchemaError::EncodingError)?;
+                Value::Json(Box::new(json))
+            }
+        };
+
+        Ok(val)
+    }
+}
+
+impl PartialEq<Value> for ValueRef<'_> {
+    fn eq(&self, other: &Value) -> bool {
+        match (self, other) {
+            (Self::Null, Value::Null) => true,
+            (Self::String(s1), Value::String(s2)) => s1 == s2,
+            (Self::Bytes(b1), Value::Bytes(b2)) => b1 == b2,
+            (Self::F64(f1), Value::F64(f2)) => f1 == f2,
+            (Self::I64(i1), Value::I64(i2)) => i1 == i2,
+            (Self::U64(u1), Value::U64(u2)) => u1 == u2,
+            (Self::I128(i1), Value::I128(i2)) => i1 == i2,
+            (Self::U128(u1), Value::U128(u2)) => u1 == u2,
+            (Self::Bool(b1), Value::Bool(b2)) => b1 == b2,
+            (Self::Timestamp(t1), Value::Timestamp(t2)) => t1 == t2,
+            (Self::Date(d1), Value::Date(d2)) => d1 == d2,
+            (Self::Time(t1), Value::Time(t2)) => t1 == t2,
+            (Self::Duration(d1), Value::Duration(d2)) => d1 == d2,
+            (Self::Decimal(d1), Value::Decimal(d2)) => d1 == d2,
+            (Self::Uuid(u1), Value::Uuid(u2)) => u1 == u2,
+            (Self::List(_), Value::List(_))
+            | (Self::Map(_), Value::Map(_))
+            | (Self::Struct(_), Value::Struct(_)) => self.to_value().as_ref() == Ok(other),
+            #[cfg(feature = "json")]
+            (Self::Json(_), Value::Json(_)) => self.to_value().as_ref() == Ok(other),
+            _ => false,
+        }
+    }
+}
+
+impl<'a> From<&'a str> for ValueRef<'a> {
+    fn from(s: &'a str) -> Self {
+        Self::String(s)
+    }
+}
+
+impl<'a> From<&'a [u8]> for ValueRef<'a> {
+    fn from(b: &'a [u8]) -> Self {
+        Self::Bytes(b)
+    }
+}
+
+impl From<i64> for ValueRef<'_> {
+    fn from(i: i64) -> Self {
+        Self::I64(i)
+    }
+}
+
+impl From<u64> for ValueRef<'_> {
+    fn from(u: u64) -> Self {
+        Self::U64(u)
+    }
+}
+
+impl From<i128> for ValueRef<'_> {
+    fn from(i: i128) -> Self {
+        Self::I128(i)
+    }
+}
+
+impl From<u128> for ValueRef<'_> {
+    fn from(u: u128) -> Self {
+        Self::U128(u)
+    }
+}
+
+impl From<f64> for ValueRef<'_> {
+    fn from(f: f64) -> Self {
+        Self::F64(f)
+    }
+}
+
+impl From<bool> for ValueRef<'_> {
+    fn from(b: bool) -> Self {
+        Self::Bool(b)
+    }
+}